@@ -25,6 +25,7 @@ done
 # miri
 
 cargo +nightly miri test -p embedded-command command_buffer
+cargo +nightly miri test -p cookie-cutter decode
 
 # clippy
 cargo clippy
@@ -33,5 +34,9 @@ cargo clippy
 
 # cookie-cutter
 
+# integrations
+cargo test -p cookie-cutter --features bitflags,fixed,half,heapless
+cargo test -p cookie-cutter --features detailed-errors
+
 # asm analysis
 cargo build -p cookie-cutter --bin asm --target thumbv7em-none-eabihf --features binary --release
@@ -10,3 +10,14 @@ pub trait Encoding {
     /// i.e. `u8` for `[u8; ...]` mediums.
     type Word;
 }
+
+/// The order in which the words of
+/// multi-word values are serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Endian {
+    /// Least significant word first.
+    Little,
+    /// Most significant word first.
+    Big,
+}
@@ -1,12 +1,34 @@
+#[cfg(feature = "bitflags")]
+pub mod bitflags;
+pub mod decode;
+#[cfg(feature = "fixed")]
+mod fixed;
+#[cfg(feature = "half")]
+mod half;
+pub mod layout;
+pub mod narrow;
+pub mod padding;
+pub mod pod;
+pub mod prefixed;
+pub mod scale;
+pub mod with;
+
+#[cfg(feature = "detailed-errors")]
+use core::cell::Cell;
 use core::{marker::PhantomData, mem::MaybeUninit};
 
-use super::Encoding;
+use super::{Encoding, Endian};
 
-use crate::{error, SerializeBuf, SerializeIter};
+use crate::{
+    error,
+    medium::{Concat, Medium, Repeat},
+    schema::{FloatFormat, Kind, Schema},
+    SerializeBuf, SerializeIter,
+};
 
 use fill_array::fill;
 // export proc macro
-pub use macros::{SerializeBuf, SerializeIter};
+pub use macros::{Decode, SerializeBuf, SerializeIter, SerializeView};
 
 pub struct Vanilla;
 impl Encoding for Vanilla {
@@ -14,29 +36,60 @@ impl Encoding for Vanilla {
 }
 
 macro_rules! impl_number {
-    ($TYPE:ty, $SIZE:expr) => {
+    ($TYPE:ty, $SIZE:expr, $KIND:expr) => {
         impl SerializeIter for $TYPE {
             fn serialize_iter<'a, 'b>(
                 &'a self,
                 dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
             ) -> Result<(), error::EndOfInput>
+            where
+                <Vanilla as Encoding>::Word: 'b,
+            {
+                // le because most no_std targets are LE native
+                self.serialize_iter_endian(dst, Endian::Little)
+            }
+
+            fn deserialize_iter<'a>(
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+            ) -> Result<Self, error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                // le because most no_std targets are LE native
+                Self::deserialize_iter_endian(src, Endian::Little)
+            }
+
+            fn serialized_len(&self) -> usize {
+                $SIZE
+            }
+
+            fn serialize_iter_endian<'a, 'b>(
+                &'a self,
+                dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<(), error::EndOfInput>
             where
                 <Vanilla as Encoding>::Word: 'b,
             {
                 let mut dst = dst.into_iter();
 
+                let bytes = match endian {
+                    Endian::Little => self.to_le_bytes(),
+                    Endian::Big => self.to_be_bytes(),
+                };
+
                 // 1. vanilla encoding uses bytes
                 // 2. length constraint is on dest, not the type
-                // 3. le_bytes because most no_std targets are LE native
-                for byte in self.to_le_bytes() {
+                for byte in bytes {
                     *dst.next().ok_or(error::EndOfInput)? = byte;
                 }
 
                 Ok(())
             }
 
-            fn deserialize_iter<'a>(
+            fn deserialize_iter_endian<'a>(
                 src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
             ) -> Result<Self, error::Error>
             where
                 <Vanilla as Encoding>::Word: 'a,
@@ -47,14 +100,22 @@ macro_rules! impl_number {
                 // 2. all byte values are valid
                 let bytes = fill![*src.next().ok_or(error::EndOfInput)?; $SIZE];
 
-                // le_bytes because most no_std targets are LE native
-                Ok(Self::from_le_bytes(bytes))
+                Ok(match endian {
+                    Endian::Little => Self::from_le_bytes(bytes),
+                    Endian::Big => Self::from_be_bytes(bytes),
+                })
             }
         }
 
         // SAFETY: $SIZE must be correct as it is validated by it's usage with `from_le_bytes`
         unsafe impl SerializeBuf for $TYPE {
             type Serialized = [u8; $SIZE];
+
+            const SCHEMA: &'static Schema = &Schema {
+                name: stringify!($TYPE),
+                size: $SIZE,
+                kind: $KIND,
+            };
         }
     };
 }
@@ -64,16 +125,28 @@ macro_rules! impl_number {
 // isize/usize have platform specific size!
 // NOTE: getting the "size" values wrong here
 // will result in a compile-timer error, not UB
-impl_number!(u8, 1);
-impl_number!(u16, 2);
-impl_number!(u32, 4);
-impl_number!(u64, 8);
-impl_number!(i8, 1);
-impl_number!(i16, 2);
-impl_number!(i32, 4);
-impl_number!(i64, 8);
-impl_number!(f32, 4);
-impl_number!(f64, 8);
+impl_number!(u8, 1, Kind::Unsigned);
+impl_number!(u16, 2, Kind::Unsigned);
+impl_number!(u32, 4, Kind::Unsigned);
+impl_number!(u64, 8, Kind::Unsigned);
+impl_number!(i8, 1, Kind::Signed);
+impl_number!(i16, 2, Kind::Signed);
+impl_number!(i32, 4, Kind::Signed);
+impl_number!(i64, 8, Kind::Signed);
+impl_number!(
+    f32,
+    4,
+    Kind::Float {
+        format: FloatFormat::Binary32
+    }
+);
+impl_number!(
+    f64,
+    8,
+    Kind::Float {
+        format: FloatFormat::Binary64
+    }
+);
 
 // bool impls
 
@@ -106,10 +179,35 @@ impl SerializeIter for bool {
             _ => Err(error::Invalid)?,
         }
     }
+
+    fn serialized_len(&self) -> usize {
+        1
+    }
+
+    #[cfg(feature = "detailed-errors")]
+    #[allow(clippy::result_large_err)]
+    fn deserialize_iter_detailed<'a>(
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        _endian: Endian,
+    ) -> Result<Self, error::Detailed>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        Self::deserialize_iter(src).map_err(|err| match err {
+            error::Error::Invalid => error::Reason::BadBool.into(),
+            err => err.into(),
+        })
+    }
 }
 
 unsafe impl SerializeBuf for bool {
     type Serialized = [u8; 1];
+
+    const SCHEMA: &'static Schema = &Schema {
+        name: "bool",
+        size: 1,
+        kind: Kind::Bool,
+    };
 }
 
 // array impls
@@ -119,20 +217,46 @@ impl<T: SerializeIter, const N: usize> SerializeIter for [T; N] {
         &'a self,
         dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
     ) -> Result<(), error::EndOfInput>
+    where
+        <Vanilla as Encoding>::Word: 'b,
+    {
+        self.serialize_iter_endian(dst, Endian::Little)
+    }
+
+    fn deserialize_iter<'a>(
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+    ) -> Result<Self, error::Error>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        Self::deserialize_iter_endian(src, Endian::Little)
+    }
+
+    fn serialized_len(&self) -> usize {
+        // elements may differ in length, e.g. enums
+        self.iter().map(SerializeIter::serialized_len).sum()
+    }
+
+    fn serialize_iter_endian<'a, 'b>(
+        &'a self,
+        dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+        endian: Endian,
+    ) -> Result<(), error::EndOfInput>
     where
         <Vanilla as Encoding>::Word: 'b,
     {
         let mut dst = dst.into_iter();
 
         for item in self {
-            item.serialize_iter(&mut dst)?;
+            item.serialize_iter_endian(&mut dst, endian)?;
         }
 
         Ok(())
     }
 
-    fn deserialize_iter<'a>(
+    fn deserialize_iter_endian<'a>(
         src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        endian: Endian,
     ) -> Result<Self, error::Error>
     where
         <Vanilla as Encoding>::Word: 'a,
@@ -144,15 +268,84 @@ impl<T: SerializeIter, const N: usize> SerializeIter for [T; N] {
         let mut result: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
 
         for value in result.iter_mut() {
-            value.write(T::deserialize_iter(&mut src)?);
+            value.write(T::deserialize_iter_endian(&mut src, endian)?);
+        }
+
+        // SAFETY: by now all elements are initialized
+        Ok(result.map(|e| unsafe { e.assume_init() }))
+    }
+
+    #[cfg(feature = "detailed-errors")]
+    #[allow(clippy::result_large_err)]
+    fn deserialize_iter_detailed<'a>(
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        endian: Endian,
+    ) -> Result<Self, error::Detailed>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        // words are counted to locate failing elements
+        let offset = Cell::new(0usize);
+        let mut src = src.into_iter().inspect(|_| offset.set(offset.get() + 1));
+
+        // SAFETY: `result` is purely written to
+        let mut result: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
+
+        for (i, value) in result.iter_mut().enumerate() {
+            let start = offset.get();
+
+            value.write(
+                T::deserialize_iter_detailed(&mut src, endian)
+                    .map_err(|err| err.within(error::Segment::Index(i), start))?,
+            );
         }
 
         // SAFETY: by now all elements are initialized
         Ok(result.map(|e| unsafe { e.assume_init() }))
     }
+
+    fn deserialize_in_place<'a>(
+        &mut self,
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+    ) -> Result<(), error::Error>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        self.deserialize_in_place_endian(src, Endian::Little)
+    }
+
+    fn deserialize_in_place_endian<'a>(
+        &mut self,
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        endian: Endian,
+    ) -> Result<(), error::Error>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        let mut src = src.into_iter();
+
+        // elements are overwritten one at a time
+        for item in self {
+            item.deserialize_in_place_endian(&mut src, endian)?;
+        }
+
+        Ok(())
+    }
 }
 
-// implementing `SerializeBuf` for generic arrays requires the "generic_const_exprs" feature
+// SAFETY: each element requires `T::Serialized` and there are `N` elements
+unsafe impl<T: SerializeBuf, const N: usize> SerializeBuf for [T; N] {
+    type Serialized = Repeat<T::Serialized, N>;
+
+    const SCHEMA: &'static Schema = &Schema {
+        name: "",
+        size: <Self::Serialized as Medium>::SIZE,
+        kind: Kind::Array {
+            len: N,
+            item: T::SCHEMA,
+        },
+    };
+}
 
 // tuple impls
 
@@ -163,6 +356,32 @@ macro_rules! impl_tuple {
                 &'a self,
                 dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
             ) -> Result<(), error::EndOfInput>
+            where
+                <Vanilla as Encoding>::Word: 'b,
+            {
+                self.serialize_iter_endian(dst, Endian::Little)
+            }
+
+            fn deserialize_iter<'a>(
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+            ) -> Result<Self, error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                Self::deserialize_iter_endian(src, Endian::Little)
+            }
+
+            fn serialized_len(&self) -> usize {
+                let ($($NAME,)+) = self;
+
+                0 $(+ $NAME.serialized_len())+
+            }
+
+            fn serialize_iter_endian<'a, 'b>(
+                &'a self,
+                dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<(), error::EndOfInput>
             where
                 <Vanilla as Encoding>::Word: 'b,
             {
@@ -171,14 +390,15 @@ macro_rules! impl_tuple {
                 let ($($NAME,)+) = self;
 
                 $(
-                    $NAME.serialize_iter(&mut dst)?;
+                    $NAME.serialize_iter_endian(&mut dst, endian)?;
                 )+
 
                 Ok(())
             }
 
-            fn deserialize_iter<'a>(
+            fn deserialize_iter_endian<'a>(
                 src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
             ) -> Result<Self, error::Error>
             where
                 <Vanilla as Encoding>::Word: 'a,
@@ -186,16 +406,63 @@ macro_rules! impl_tuple {
                 let mut src = src.into_iter();
 
                 $(
-                    let $NAME = $TYPE::deserialize_iter(&mut src)?;
+                    let $NAME = $TYPE::deserialize_iter_endian(&mut src, endian)?;
                 )+
 
                 Ok(($($NAME,)+))
             }
+
+            fn deserialize_in_place<'a>(
+                &mut self,
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+            ) -> Result<(), error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                self.deserialize_in_place_endian(src, Endian::Little)
+            }
+
+            fn deserialize_in_place_endian<'a>(
+                &mut self,
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<(), error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                let mut src = src.into_iter();
+
+                let ($($NAME,)+) = self;
+
+                $(
+                    $NAME.deserialize_in_place_endian(&mut src, endian)?;
+                )+
+
+                Ok(())
+            }
+        }
+
+        // SAFETY: each element requires its own `Serialized` and they are concatenated in order
+        unsafe impl<$($TYPE: SerializeBuf),+> SerializeBuf for ($($TYPE,)+) {
+            type Serialized = concat_medium!($($TYPE),+);
+
+            const SCHEMA: &'static Schema = &Schema {
+                name: "",
+                size: <Self::Serialized as Medium>::SIZE,
+                kind: Kind::Tuple(&[$($TYPE::SCHEMA),+]),
+            };
         }
     };
 }
 
-// implementing `SerializeBuf` for generic tuples requires the "generic_const_exprs" feature
+macro_rules! concat_medium {
+    ($TYPE:ident) => {
+        <$TYPE as SerializeBuf>::Serialized
+    };
+    ($TYPE:ident, $($REST:ident),+) => {
+        Concat<<$TYPE as SerializeBuf>::Serialized, concat_medium!($($REST),+)>
+    };
+}
 
 // NOTE: incorrect macro arguments will result in compile-time error, not UB
 impl_tuple!((A, a));
@@ -227,6 +494,21 @@ impl<T> SerializeIter for PhantomData<T> {
     {
         Ok(PhantomData)
     }
+
+    fn serialized_len(&self) -> usize {
+        0
+    }
+}
+
+// SAFETY: nothing is serialized
+unsafe impl<T> SerializeBuf for PhantomData<T> {
+    type Serialized = [u8; 0];
+
+    const SCHEMA: &'static Schema = &Schema {
+        name: "PhantomData",
+        size: 0,
+        kind: Kind::Tuple(&[]),
+    };
 }
 
 #[cfg(test)]
@@ -300,6 +582,7 @@ mod tests {
         }
 
         #[test]
+        #[allow(clippy::unnecessary_mut_passed)]
         fn buf() {
             // numbers
 
@@ -337,10 +620,12 @@ mod tests {
     // rust analyzer cannot cope with recursive crate import
     #[cfg(test)]
     mod derive {
-        use core::marker::PhantomData;
+        use core::{marker::PhantomData, mem::size_of};
 
         use crate as cookie_cutter; // for the proc macro
-        use cookie_cutter::{encoding::vanilla, SerializeBuf, SerializeIter};
+        use cookie_cutter::{
+            encoding::vanilla, error, medium::Medium, SerializeBuf, SerializeIter,
+        };
 
         mod structs {
             use super::*;
@@ -445,6 +730,315 @@ mod tests {
             }
         }
 
+        #[test]
+        fn skip() {
+            struct NotSerializable;
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Foo {
+                a: u8,
+                #[cookie(skip)]
+                cache: Option<u32>,
+                #[cookie(skip, default = 0xbeef)]
+                b: u16,
+                c: i8,
+                #[cookie(skip)]
+                marker: PhantomData<NotSerializable>,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Cached(#[cookie(skip)] u32);
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Bar {
+                A(#[cookie(skip)] u32, u8),
+                B {
+                    #[cookie(skip, default = true)]
+                    cached: bool,
+                },
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(2, buf.len());
+
+            Foo {
+                a: 0xaa,
+                cache: Some(1),
+                b: 2,
+                c: -1,
+                marker: PhantomData,
+            }
+            .serialize_buf(&mut buf);
+            assert_eq!([0xaa, 0xff], buf);
+
+            assert_eq!(
+                Foo {
+                    a: 0xaa,
+                    cache: None,
+                    b: 0xbeef,
+                    c: -1,
+                    marker: PhantomData,
+                },
+                Foo::deserialize_buf(&buf).unwrap()
+            );
+
+            assert_eq!(0, <Cached as SerializeBuf>::Serialized::default().len());
+            assert_eq!(Cached(0), Cached::deserialize_buf(&[]).unwrap());
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(2, buf.len());
+
+            Bar::A(1, 2).serialize_buf(&mut buf);
+            assert_eq!([0, 2], buf);
+            assert_eq!(Bar::A(0, 2), Bar::deserialize_buf(&buf).unwrap());
+
+            Bar::B { cached: false }.serialize_buf(&mut buf);
+            assert_eq!(Bar::B { cached: true }, Bar::deserialize_buf(&buf).unwrap());
+        }
+
+        #[test]
+        fn with() {
+            use core::time::Duration;
+
+            // a foreign type serialized as milliseconds
+            mod millis {
+                use core::time::Duration;
+
+                use crate::{encoding::vanilla::with::Size, error, SerializeIter};
+
+                // SAFETY: a `u32` is serialized
+                pub const SIZE: Size = unsafe { Size::new(4) };
+
+                pub fn serialize_iter<'a>(
+                    value: &Duration,
+                    dst: impl IntoIterator<Item = &'a mut u8>,
+                ) -> Result<(), error::EndOfInput> {
+                    (value.as_millis() as u32).serialize_iter(dst)
+                }
+
+                pub fn deserialize_iter<'a>(
+                    src: impl IntoIterator<Item = &'a u8>,
+                ) -> Result<Duration, error::Error> {
+                    Ok(Duration::from_millis(u32::deserialize_iter(src)?.into()))
+                }
+
+                pub fn serialized_len(_value: &Duration) -> usize {
+                    SIZE.get()
+                }
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Foo {
+                a: u8,
+                #[cookie(with = millis)]
+                b: Duration,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Bar<T: SerializeBuf> {
+                A(T, #[cookie(with = millis)] Duration),
+                B,
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(5, buf.len());
+
+            let test_foo = Foo {
+                a: 0xaa,
+                b: Duration::from_millis(0x1234),
+            };
+            test_foo.serialize_buf(&mut buf);
+            assert_eq!([0xaa, 0x34, 0x12, 0, 0], buf);
+            assert_eq!(5, test_foo.serialized_len());
+
+            assert_eq!(test_foo, Foo::deserialize_buf(&buf).unwrap());
+
+            type Serialized = <Bar<u16> as SerializeBuf>::Serialized;
+            assert_eq!(7, <Serialized as Medium>::SIZE);
+
+            let mut buf = Serialized::default();
+
+            let test_bar = Bar::A(0xffu16, Duration::from_secs(1));
+            test_bar.serialize_buf(&mut buf);
+
+            assert_eq!(test_bar, Bar::deserialize_buf(&buf).unwrap());
+        }
+
+        #[test]
+        fn endian() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u16)]
+            enum Word {
+                A(u16) = 0x0102,
+                B(i32),
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Payload {
+                a: u16,
+                b: [Word; 2],
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(endian = "big")]
+            struct Header {
+                a: u16,
+                #[cookie(endian = "little")]
+                b: u16,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Frame {
+                header: Header,
+                #[cookie(endian = "big")]
+                payload: Payload,
+                trailer: (u16, Header),
+            }
+
+            let mut buf = <Frame as SerializeBuf>::Serialized::default();
+            assert_eq!(24, buf.len());
+
+            let test_frame = Frame {
+                header: Header {
+                    a: 0x1122,
+                    b: 0x3344,
+                },
+                payload: Payload {
+                    a: 0x5566,
+                    b: [Word::A(0x7788), Word::B(0x99aabbcc_u32 as i32)],
+                },
+                trailer: (
+                    0xddee,
+                    Header {
+                        a: 0x1122,
+                        b: 0x3344,
+                    },
+                ),
+            };
+            test_frame.serialize_buf(&mut buf);
+
+            assert_eq!(
+                [
+                    0x11, 0x22, 0x44, 0x33, // header: big, little
+                    0x55, 0x66, // payload: big...
+                    0x01, 0x02, 0x77, 0x88, // ...recursively, including tags
+                    0x01, 0x03, 0x99, 0xaa, 0xbb, 0xcc, //
+                    0xee, 0xdd, // trailer: little
+                    0x11, 0x22, 0x44, 0x33, // header is always big, except for `b`
+                    0x00, 0x00,
+                ],
+                buf
+            );
+
+            assert_eq!(test_frame, Frame::deserialize_buf(&buf).unwrap());
+        }
+
+        #[test]
+        fn validate() {
+            fn is_even(value: &u8) -> bool {
+                value & 1 == 0
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(validate = |foo: &Self| foo.min <= foo.max)]
+            struct Foo {
+                #[cookie(range = 0..=100)]
+                min: u8,
+                #[cookie(range = 0..=100, validate = is_even)]
+                max: u8,
+                #[cookie(scale = 0.1, repr = i8, range = -1.0..1.0)]
+                gain: f32,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Bar {
+                A(#[cookie(validate = is_even)] u8),
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+
+            let test_foo = Foo {
+                min: 10,
+                max: 20,
+                gain: 0.5,
+            };
+            test_foo.serialize_buf(&mut buf);
+
+            assert_eq!(test_foo, Foo::deserialize_buf(&buf).unwrap());
+
+            // field constraints
+            for invalid in [[101, 102, 0], [0, 21, 0], [0, 0, 10], [0, 0, 0xf5]] {
+                match Foo::deserialize_iter(invalid.iter()) {
+                    Err(error::Error::Invalid) => {}
+                    _ => panic!(),
+                }
+            }
+
+            // container constraint
+            assert!(Foo::deserialize_buf(&[20, 10, 0]).is_err());
+
+            assert_eq!(Bar::A(2), Bar::deserialize_buf(&[0, 2]).unwrap());
+            assert!(Bar::deserialize_buf(&[0, 3]).is_err());
+        }
+
+        #[test]
+        fn fallback() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Foo {
+                A(u16),
+                #[cookie(fallback)]
+                Unknown(u8),
+                B = 0xb,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u16)]
+            enum Bar {
+                A,
+                #[cookie(fallback)]
+                Unknown {
+                    tag: u16,
+                },
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(3, buf.len());
+
+            for test_foo in [Foo::A(0xbeef), Foo::B] {
+                test_foo.serialize_buf(&mut buf);
+
+                assert_eq!(test_foo, Foo::deserialize_buf(&buf).unwrap());
+            }
+
+            // unknown tags are captured...
+            let buf = [0xff, 0xaa, 0xbb];
+            let read_foo = Foo::deserialize_buf(&buf).unwrap();
+            assert_eq!(Foo::Unknown(0xff), read_foo);
+
+            // ...and re-emitted
+            let mut buf = [0; 1];
+            read_foo.serialize_iter(buf.iter_mut()).unwrap();
+            assert_eq!([0xff], buf);
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(2, buf.len());
+
+            Bar::Unknown { tag: 0x1234 }.serialize_buf(&mut buf);
+            assert_eq!([0x34, 0x12], buf);
+
+            assert_eq!(
+                Bar::Unknown { tag: 0x1234 },
+                Bar::deserialize_buf(&buf).unwrap()
+            );
+
+            buf = [0, 0];
+            assert_eq!(Bar::A, Bar::deserialize_buf(&buf).unwrap());
+        }
+
         #[test]
         fn generics() {
             const BE: u8 = 0xbe;
@@ -489,5 +1083,1479 @@ mod tests {
 
             assert_eq!(test_bar, read_bar); // comparison provides type inference for deserialization!
         }
+
+        #[test]
+        fn generics_buf() {
+            const BE: u8 = 0xbe;
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u16)]
+            enum FooGen<T, U>
+            where
+                T: SerializeBuf,
+                U: SerializeBuf,
+            {
+                A(u8, T),
+                B { woah: U } = BE as u16,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct BarGen<T>
+            where
+                T: SerializeBuf,
+            {
+                a: T,
+                b: FooGen<bool, T>,
+                c: PhantomData<T>,
+            }
+
+            // a: 2, b: 2 + max(1 + 1, 2), c: 0
+            type Serialized = <BarGen<i16> as SerializeBuf>::Serialized;
+            assert_eq!(6, <Serialized as Medium>::SIZE);
+            assert_eq!(6, size_of::<Serialized>());
+
+            let mut buf = Serialized::default();
+
+            for test_bar in [
+                BarGen {
+                    a: -1i16,
+                    b: FooGen::A(0xaa, false),
+                    c: PhantomData,
+                },
+                BarGen {
+                    a: 0x1234i16,
+                    b: FooGen::B { woah: -2 },
+                    c: PhantomData,
+                },
+            ] {
+                test_bar.serialize_buf(&mut buf);
+
+                let read_bar = BarGen::deserialize_buf(&buf).unwrap();
+
+                assert_eq!(test_bar, read_bar);
+            }
+        }
+
+        #[test]
+        fn scale() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Foo {
+                #[cookie(scale = 0.01, repr = i16)]
+                a: f32,
+                #[cookie(scale = 0.5, repr = u8)]
+                b: f64,
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(3, buf.len());
+
+            Foo {
+                a: -12.34,
+                b: 100.0,
+            }
+            .serialize_buf(&mut buf);
+            assert_eq!([0x2e, 0xfb, 200], buf); // -1234, 200
+
+            let read_foo = Foo::deserialize_buf(&buf).unwrap();
+
+            assert!((read_foo.a + 12.34).abs() < 0.005);
+            assert_eq!(100.0, read_foo.b);
+
+            // values beyond the representation saturate
+            Foo { a: 1e6, b: -3.0 }.serialize_buf(&mut buf);
+            assert_eq!([0xff, 0x7f, 0], buf); // i16::MAX, u8::MIN
+        }
+
+        #[test]
+        fn narrow() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Foo(#[cookie(repr = f32)] f64);
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(4, buf.len());
+
+            Foo(0.1).serialize_buf(&mut buf);
+
+            assert_eq!(Foo(0.1f32 as f64), Foo::deserialize_buf(&buf).unwrap());
+        }
+
+        #[test]
+        fn composites() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Foo {
+                a: [u16; 3],
+                b: (u8, bool, [i8; 2]),
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(10, buf.len());
+
+            let test_foo = Foo {
+                a: [1, 2, 0xffff],
+                b: (0xaa, true, [-1, 1]),
+            };
+            test_foo.serialize_buf(&mut buf);
+
+            let read_foo = Foo::deserialize_buf(&buf).unwrap();
+
+            assert_eq!(test_foo, read_foo);
+        }
+
+        #[test]
+        fn len() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Foo {
+                count: u8,
+                #[cookie(len = count)]
+                items: [u16; 4],
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Bar {
+                A(u8, #[cookie(len = 0)] [i8; 2]),
+            }
+
+            // sized to the capacity
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(9, buf.len());
+
+            let test_foo = Foo {
+                count: 2,
+                items: [1, 0x0302, 0, 0],
+            };
+            test_foo.serialize_buf(&mut buf);
+
+            assert_eq!([2, 1, 0, 2, 3, 0, 0, 0, 0], buf);
+            assert_eq!(test_foo, Foo::deserialize_buf(&buf).unwrap());
+
+            // counts beyond the capacity are rejected
+            assert!(matches!(
+                Foo::deserialize_iter([5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0].iter()),
+                Err(error::Error::Invalid)
+            ));
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(4, buf.len());
+
+            Bar::A(1, [-1, -2]).serialize_buf(&mut buf);
+
+            assert_eq!([0, 1, 0xff, 0], buf);
+            assert_eq!(Bar::A(1, [-1, 0]), Bar::deserialize_buf(&buf).unwrap());
+
+            #[derive(
+                Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf, vanilla::Decode,
+            )]
+            struct Baz {
+                count: i8,
+                #[cookie(len = count)]
+                items: [u8; 2],
+            }
+
+            // counts beyond the elements present select those present, negative counts none
+            let mut buf = <Baz as SerializeBuf>::Serialized::default();
+
+            for (count, words, len) in [(3, [3, 1, 2], 3), (-1, [0xff, 0, 0], 1)] {
+                buf = Default::default();
+
+                let test_baz = Baz {
+                    count,
+                    items: [1, 2],
+                };
+                test_baz.serialize_buf(&mut buf);
+
+                assert_eq!(words, buf);
+                assert_eq!(len, test_baz.serialized_len());
+            }
+
+            // negative counts are rejected upon deserialization
+            assert!(Baz::deserialize_buf(&buf).is_err());
+            assert!(matches!(
+                vanilla::decode::Decoder::<Baz>::new().feed(buf.iter()),
+                core::task::Poll::Ready(Err(_))
+            ));
+        }
+
+        #[test]
+        fn magic() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xa55a)]
+            struct Foo {
+                #[cookie(magic = 2)]
+                version: u8,
+                a: u8,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = b"OK", endian = "big")]
+            #[repr(u8)]
+            enum Bar {
+                A(u16),
+                B,
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(4, buf.len());
+
+            // the constant is serialized regardless of the field
+            Foo { version: 0, a: 7 }.serialize_buf(&mut buf);
+            assert_eq!([0x5a, 0xa5, 2, 7], buf);
+            assert_eq!(
+                Foo { version: 2, a: 7 },
+                Foo::deserialize_buf(&buf).unwrap()
+            );
+
+            // mismatches are rejected
+            for invalid in [[0x5a, 0xa4, 2, 7], [0x5a, 0xa5, 3, 7]] {
+                assert!(matches!(
+                    Foo::deserialize_iter(invalid.iter()),
+                    Err(error::Error::Invalid)
+                ));
+            }
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(5, buf.len());
+
+            Bar::A(0x1234).serialize_buf(&mut buf);
+            assert_eq!(*b"OK\x00\x12\x34", buf);
+            assert_eq!(Bar::A(0x1234), Bar::deserialize_buf(&buf).unwrap());
+
+            assert!(matches!(
+                Bar::deserialize_iter(b"NO\x01".iter()),
+                Err(error::Error::Invalid)
+            ));
+        }
+
+        #[test]
+        fn padding() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(align = 4)]
+            struct Foo {
+                a: u8,
+                #[cookie(pad = 1)]
+                b: u8,
+                #[cookie(align = 4)]
+                c: u16,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(reserved = "zero")]
+            #[repr(u8)]
+            enum Bar {
+                A(#[cookie(align = 2)] u16),
+                B,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(pad = 1)]
+            struct BazGen<T: SerializeBuf> {
+                #[cookie(pad = 2)]
+                a: T,
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(8, buf.len());
+
+            let test_foo = Foo {
+                a: 1,
+                b: 2,
+                c: 0x0403,
+            };
+            test_foo.serialize_buf(&mut buf);
+            assert_eq!([1, 0, 2, 0, 3, 4, 0, 0], buf);
+
+            // reserved words are ignored by default
+            assert_eq!(
+                test_foo,
+                Foo::deserialize_buf(&[1, 0xff, 2, 0xff, 3, 4, 0xff, 0xff]).unwrap()
+            );
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(4, buf.len());
+
+            Bar::A(0x0201).serialize_buf(&mut buf);
+            assert_eq!([0, 0, 1, 2], buf);
+            assert_eq!(Bar::A(0x0201), Bar::deserialize_buf(&buf).unwrap());
+
+            assert!(matches!(
+                Bar::deserialize_iter([0, 1, 1, 2].iter()),
+                Err(error::Error::Invalid)
+            ));
+
+            type Serialized = <BazGen<u16> as SerializeBuf>::Serialized;
+            assert_eq!(5, <Serialized as Medium>::SIZE);
+
+            let mut buf = [0xff; 5];
+
+            BazGen { a: 0x0201u16 }
+                .serialize_iter(buf.iter_mut())
+                .unwrap();
+            assert_eq!([0, 0, 1, 2, 0], buf);
+        }
+
+        #[test]
+        fn versions() {
+            // version 1 had `a` and `b`, version 2 added `c`, version 3 removed `b`
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(version = 3)]
+            struct Foo {
+                a: u8,
+                #[cookie(until = 3, default = 0xff)]
+                b: u8,
+                #[cookie(since = 2)]
+                c: u16,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(version = 2u16)]
+            #[repr(u8)]
+            enum Bar {
+                A(#[cookie(since = 2)] u8),
+            }
+
+            // sized for every version
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(5, buf.len());
+
+            let test_foo = Foo {
+                a: 1,
+                b: 2,
+                c: 0x0403,
+            };
+            test_foo.serialize_buf(&mut buf);
+            assert_eq!([3, 1, 3, 4, 0], buf);
+
+            assert_eq!(
+                Foo {
+                    a: 1,
+                    b: 0xff,
+                    c: 0x0403
+                },
+                Foo::deserialize_buf(&buf).unwrap()
+            );
+
+            // historical layouts
+            assert_eq!(
+                Foo { a: 1, b: 2, c: 0 },
+                Foo::deserialize_iter([1, 1, 2].iter()).unwrap()
+            );
+            assert_eq!(
+                Foo {
+                    a: 1,
+                    b: 2,
+                    c: 0x0403
+                },
+                Foo::deserialize_iter([2, 1, 2, 3, 4].iter()).unwrap()
+            );
+
+            // future versions are rejected
+            assert!(matches!(
+                Foo::deserialize_iter([4, 1, 2, 3, 4].iter()),
+                Err(error::Error::Invalid)
+            ));
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(4, buf.len());
+
+            Bar::A(5).serialize_buf(&mut buf);
+            assert_eq!([2, 0, 0, 5], buf);
+            assert_eq!(Bar::A(0), Bar::deserialize_iter([1, 0, 0].iter()).unwrap());
+        }
+
+        #[test]
+        fn hashed_tags() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(tag = hash)]
+            #[repr(u32)]
+            enum Foo {
+                Ping,
+                Pong,
+                Data(u8),
+            }
+
+            // reordered, signed
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(tag = hash)]
+            #[repr(i8)]
+            enum Bar {
+                Data(u8),
+                Pong,
+                Ping,
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(5, buf.len());
+
+            Foo::Ping.serialize_buf(&mut buf);
+            assert_eq!([0x89, 0x7b, 0xa3, 0xc6], buf[..4]);
+
+            Foo::Data(7).serialize_buf(&mut buf);
+            assert_eq!([0xe5, 0xe1, 0x2e, 0x2f, 7], buf);
+            assert_eq!(Foo::Data(7), Foo::deserialize_buf(&buf).unwrap());
+
+            // the order of variants does not matter
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+
+            for (bar, tag) in [(Bar::Ping, 0x89), (Bar::Pong, 0x77), (Bar::Data(7), 0xe5)] {
+                bar.serialize_buf(&mut buf);
+                assert_eq!(tag, buf[0]);
+                assert_eq!(bar, Bar::deserialize_buf(&buf).unwrap());
+            }
+        }
+
+        #[test]
+        fn tag_type() {
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            enum Foo {
+                A,
+                B = 0xff,
+                C,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(tag_type = u8)]
+            #[repr(u32)]
+            enum Bar {
+                A(u16),
+                B = 7,
+            }
+
+            // inferred from the largest tag
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            assert_eq!(2, buf.len());
+
+            Foo::C.serialize_buf(&mut buf);
+            assert_eq!([0, 1], buf);
+            assert_eq!(Foo::C, Foo::deserialize_buf(&buf).unwrap());
+
+            // independent of the representation
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            assert_eq!(3, buf.len());
+
+            Bar::B.serialize_buf(&mut buf);
+            assert_eq!(7, buf[0]);
+            assert_eq!(Bar::B, Bar::deserialize_buf(&buf).unwrap());
+        }
+
+        #[test]
+        fn crate_path() {
+            mod reexport {
+                pub use crate as renamed;
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(crate = reexport::renamed)]
+            struct Foo {
+                a: u16,
+            }
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+
+            Foo { a: 0x0201 }.serialize_buf(&mut buf);
+            assert_eq!([1, 2], buf);
+            assert_eq!(Foo { a: 0x0201 }, Foo::deserialize_buf(&buf).unwrap());
+        }
+
+        #[test]
+        fn layout() {
+            use cookie_cutter::encoding::vanilla::layout::FieldLayout;
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xa5)]
+            struct Foo {
+                a: u8,
+                b: u16,
+                #[cookie(skip)]
+                _c: bool,
+                #[cookie(align = 4)]
+                d: u32,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Bar {
+                A(u8, u16),
+                B { woah: u32 },
+                C,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Baz<T: SerializeBuf> {
+                a: u8,
+                b: T,
+            }
+
+            assert_eq!(1, Foo::OFFSET_A);
+            assert_eq!(2, Foo::OFFSET_B);
+            assert_eq!(2, Foo::SIZE_B);
+            assert_eq!(4, Foo::OFFSET_D);
+            assert_eq!(
+                &[
+                    FieldLayout {
+                        name: "a",
+                        offset: Some(1),
+                        size: 1
+                    },
+                    FieldLayout {
+                        name: "b",
+                        offset: Some(2),
+                        size: 2
+                    },
+                    FieldLayout {
+                        name: "d",
+                        offset: Some(4),
+                        size: 4
+                    },
+                ],
+                Foo::LAYOUT
+            );
+
+            // the offsets agree with serialization
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            Foo {
+                a: 1,
+                b: 0x0302,
+                _c: false,
+                d: 0x07060504,
+            }
+            .serialize_buf(&mut buf);
+            assert_eq!(1, buf[Foo::OFFSET_A]);
+            assert_eq!([2, 3], buf[Foo::OFFSET_B..][..Foo::SIZE_B]);
+            assert_eq!([4, 5, 6, 7], buf[Foo::OFFSET_D..][..Foo::SIZE_D]);
+
+            // variant payloads follow the tag
+            assert_eq!(1, Bar::OFFSET_A_0);
+            assert_eq!(2, Bar::OFFSET_A_1);
+            assert_eq!(1, Bar::OFFSET_B_WOAH);
+            assert_eq!(4, Bar::SIZE_B_WOAH);
+            assert!(Bar::LAYOUT
+                .iter()
+                .map(|field| field.name)
+                .eq(["A.0", "A.1", "B.woah"]));
+
+            assert_eq!(1, Baz::<u32>::OFFSET_B);
+            assert_eq!(4, Baz::<u32>::SIZE_B);
+            assert_eq!(2, Baz::<u16>::LAYOUT.len());
+        }
+
+        #[test]
+        fn variable_layout() {
+            use cookie_cutter::encoding::vanilla::layout::FieldLayout;
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Pre {
+                count: u8,
+                #[cookie(len = count)]
+                items: [u8; 4],
+                tail: u8,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Uneven {
+                A,
+                B(u32),
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct AfterUneven {
+                e: Uneven,
+                flag: bool,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Even {
+                A(u16),
+                B(i8, i8),
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct AfterEven {
+                e: Even,
+                flag: bool,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(version = 2)]
+            struct Versioned {
+                a: u8,
+                #[cookie(since = 2)]
+                b: u8,
+                c: u8,
+            }
+
+            fn offsets(layout: &[FieldLayout]) -> impl Iterator<Item = Option<usize>> + '_ {
+                layout.iter().map(|field| field.offset)
+            }
+
+            // the tail follows only as many items as counted
+            assert!(offsets(Pre::LAYOUT).eq([Some(0), Some(1), None]));
+
+            let mut buf = <Pre as SerializeBuf>::Serialized::default();
+            Pre {
+                count: 1,
+                items: [9, 0, 0, 0],
+                tail: 7,
+            }
+            .serialize_buf(&mut buf);
+            assert_eq!([1, 9, 7, 0, 0, 0], buf);
+            assert_eq!(1, buf[Pre::OFFSET_COUNT]);
+            assert_eq!(9, buf[Pre::OFFSET_ITEMS]);
+
+            // the flag follows only the variant serialized
+            assert!(!Uneven::SCHEMA.is_constant_size());
+            assert!(offsets(AfterUneven::LAYOUT).eq([Some(0), None]));
+
+            let mut buf = <AfterUneven as SerializeBuf>::Serialized::default();
+            AfterUneven {
+                e: Uneven::A,
+                flag: true,
+            }
+            .serialize_buf(&mut buf);
+            assert_eq!([0, 1, 0, 0, 0, 0], buf);
+
+            // unless all variants are of the same size
+            assert!(Even::SCHEMA.is_constant_size());
+            assert!(offsets(AfterEven::LAYOUT).eq([Some(0), Some(3)]));
+
+            let mut buf = <AfterEven as SerializeBuf>::Serialized::default();
+
+            for e in [Even::A(0x0201), Even::B(-1, 2)] {
+                AfterEven { e, flag: true }.serialize_buf(&mut buf);
+                assert_eq!(1, buf[AfterEven::OFFSET_FLAG]);
+            }
+
+            // fields following a versioned field depend on the version
+            assert!(offsets(Versioned::LAYOUT).eq([Some(1), Some(2), None]));
+            assert!(!Versioned::SCHEMA.is_constant_size());
+
+            let mut buf = <Versioned as SerializeBuf>::Serialized::default();
+            Versioned { a: 1, b: 2, c: 3 }.serialize_buf(&mut buf);
+            assert_eq!(1, buf[Versioned::OFFSET_A]);
+            assert_eq!(2, buf[Versioned::OFFSET_B]);
+        }
+
+        #[test]
+        fn view() {
+            fn is_even(value: &u16) -> bool {
+                value & 1 == 0
+            }
+
+            fn ordered(foo: &Foo) -> bool {
+                u32::from(foo.a) < foo.c
+            }
+
+            #[derive(
+                Debug,
+                PartialEq,
+                vanilla::SerializeIter,
+                vanilla::SerializeBuf,
+                vanilla::SerializeView,
+            )]
+            #[cookie(magic = 0xa5, validate = ordered)]
+            struct Foo {
+                a: u8,
+                #[cookie(skip)]
+                _b: bool,
+                #[cookie(align = 4)]
+                c: u32,
+                #[cookie(validate = is_even, endian = "big")]
+                d: u16,
+            }
+
+            #[derive(
+                Debug,
+                PartialEq,
+                vanilla::SerializeIter,
+                vanilla::SerializeBuf,
+                vanilla::SerializeView,
+            )]
+            struct Bar(#[cookie(range = 0..=100)] u8, f32);
+
+            let mut buf = <Foo as SerializeBuf>::Serialized::default();
+            Foo {
+                a: 1,
+                _b: true,
+                c: 0x1234,
+                d: 0x0102,
+            }
+            .serialize_buf(&mut buf);
+
+            let view = FooView::new(&buf).unwrap();
+            assert_eq!(1, view.a());
+            assert_eq!(0x1234, view.c());
+            assert_eq!(0x0102, view.d());
+
+            let mut view = FooViewMut::new(&mut buf).unwrap();
+            view.set_c(0x5678).unwrap();
+            view.set_d(4).unwrap();
+            assert_eq!(0x5678, view.c());
+
+            // rejected by the field and container
+            assert!(matches!(view.set_d(3), Err(error::Invalid)));
+            assert!(matches!(view.set_c(0), Err(error::Invalid)));
+            assert_eq!(0x5678, view.c());
+
+            assert_eq!([0, 4], buf[Foo::OFFSET_D..][..2]);
+            assert_eq!(
+                Foo {
+                    a: 1,
+                    _b: false,
+                    c: 0x5678,
+                    d: 4,
+                },
+                Foo::deserialize_buf(&buf).unwrap()
+            );
+
+            // validated upon construction
+            buf[0] = 0;
+            assert!(matches!(FooView::new(&buf), Err(error::Invalid)));
+
+            let mut buf = <Bar as SerializeBuf>::Serialized::default();
+            let mut view = BarViewMut::new(&mut buf).unwrap();
+            view.set_0(100).unwrap();
+            view.set_1(1.5).unwrap();
+            assert!(matches!(view.set_0(101), Err(error::Invalid)));
+            assert_eq!(Bar(100, 1.5), Bar::deserialize_buf(&buf).unwrap());
+            assert_eq!(1.5, BarView::new(&buf).unwrap().get_1());
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Mode {
+                Off,
+                On(u32),
+            }
+
+            // fields varying in size are accepted last
+            #[derive(
+                Debug,
+                PartialEq,
+                vanilla::SerializeIter,
+                vanilla::SerializeBuf,
+                vanilla::SerializeView,
+            )]
+            struct Baz {
+                flag: bool,
+                mode: Mode,
+            }
+
+            let mut buf = <Baz as SerializeBuf>::Serialized::default();
+            let mut view = BazViewMut::new(&mut buf).unwrap();
+            view.set_flag(true).unwrap();
+            view.set_mode(Mode::On(7)).unwrap();
+            assert!(view.flag());
+            assert_eq!(Mode::On(7), view.mode());
+            assert_eq!([1, 1, 7, 0, 0, 0], buf);
+        }
+
+        #[test]
+        fn in_place() {
+            #[derive(Debug, Default, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Inner {
+                a: u16,
+                #[cookie(range = 0..=10)]
+                b: u8,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[repr(u8)]
+            enum Mode {
+                Off,
+                On { level: u8 },
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xa5, pad = 1)]
+            struct Config {
+                inner: [Inner; 4],
+                #[cookie(skip, default = 7)]
+                skipped: u8,
+                #[cookie(scale = 0.5, repr = u8)]
+                scaled: f32,
+                mode: Mode,
+            }
+
+            let config = Config {
+                inner: [
+                    Inner { a: 1, b: 2 },
+                    Inner { a: 3, b: 4 },
+                    Inner { a: 5, b: 6 },
+                    Inner { a: 7, b: 8 },
+                ],
+                skipped: 7,
+                scaled: 1.5,
+                mode: Mode::On { level: 9 },
+            };
+
+            let mut buf = <Config as SerializeBuf>::Serialized::default();
+            config.serialize_buf(&mut buf);
+
+            let mut other = Config {
+                inner: Default::default(),
+                skipped: 0,
+                scaled: 0.0,
+                mode: Mode::Off,
+            };
+
+            // the variant is replaced
+            other.deserialize_in_place(buf.iter()).unwrap();
+            assert_eq!(config, other);
+
+            // the variant is overwritten
+            other.mode = Mode::On { level: 0 };
+            other.deserialize_in_place(buf.iter()).unwrap();
+            assert_eq!(config, other);
+
+            // fields are validated
+            buf[1 + 2] = 11;
+            assert!(matches!(
+                other.deserialize_in_place(buf.iter()),
+                Err(error::Error::Invalid)
+            ));
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(version = 2, validate = Self::is_valid)]
+            struct Versioned {
+                count: u8,
+                #[cookie(len = count)]
+                items: [u8; 3],
+                #[cookie(since = 2, default = 5)]
+                added: u8,
+            }
+
+            impl Versioned {
+                fn is_valid(&self) -> bool {
+                    self.added != 0
+                }
+            }
+
+            let mut versioned = Versioned {
+                count: 0,
+                items: [9; 3],
+                added: 9,
+            };
+
+            versioned
+                .deserialize_in_place([1, 2, 0xa, 0xb].iter())
+                .unwrap();
+            assert_eq!(
+                Versioned {
+                    count: 2,
+                    items: [0xa, 0xb, 0],
+                    added: 5,
+                },
+                versioned
+            );
+            assert!(matches!(
+                versioned.deserialize_in_place([2, 0, 0].iter()),
+                Err(error::Error::Invalid)
+            ));
+
+            let mut array = [0u16; 2];
+            array.deserialize_in_place([1, 2, 3, 4].iter()).unwrap();
+            assert_eq!([0x0201, 0x0403], array);
+
+            let mut tuple = (0u8, 0u16);
+            tuple
+                .deserialize_in_place_endian([1, 2, 3].iter(), crate::encoding::Endian::Big)
+                .unwrap();
+            assert_eq!((1, 0x0203), tuple);
+        }
+
+        #[test]
+        fn pod() {
+            #[derive(
+                Debug, Clone, Copy, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf,
+            )]
+            #[cookie(pod)]
+            #[repr(C)]
+            struct Sample {
+                a: u16,
+                b: i16,
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(pod)]
+            #[repr(C, align(4))]
+            struct Frame {
+                id: u32,
+                samples: [Sample; 3],
+                raw: [u8; 4],
+            }
+
+            let frame = Frame {
+                id: 0x04030201,
+                samples: [
+                    Sample { a: 0x0605, b: -2 },
+                    Sample { a: 7, b: 8 },
+                    Sample { a: 9, b: 10 },
+                ],
+                raw: [11, 12, 13, 14],
+            };
+
+            let mut buf = <Frame as SerializeBuf>::Serialized::default();
+            assert_eq!(20, buf.len());
+
+            frame.serialize_buf(&mut buf);
+            assert_eq!([1, 2, 3, 4, 5, 6, 0xfe, 0xff], buf[..8]);
+            assert_eq!([11, 12, 13, 14], buf[16..]);
+
+            // identical to the serialization via iterators
+            let mut iter_buf = <Frame as SerializeBuf>::Serialized::default();
+            frame.serialize_iter(iter_buf.iter_mut()).unwrap();
+            assert_eq!(iter_buf, buf);
+
+            assert_eq!(frame, Frame::deserialize_buf(&buf).unwrap());
+        }
+
+        #[cfg(feature = "detailed-errors")]
+        #[test]
+        fn detailed_errors() {
+            extern crate std;
+            use std::{format, string::String};
+
+            #[derive(Debug, vanilla::SerializeIter)]
+            struct Channel {
+                _enabled: bool,
+                #[cookie(range = 0..=10)]
+                _gain: u8,
+            }
+
+            #[derive(Debug, vanilla::SerializeIter)]
+            #[repr(u8)]
+            enum Mode {
+                _Off,
+                _On { level: u16 },
+            }
+
+            #[derive(Debug, vanilla::SerializeIter)]
+            #[cookie(magic = 0xa5)]
+            struct Config {
+                _id: u16,
+                _channels: [Channel; 4],
+                _mode: Mode,
+            }
+
+            fn describe(src: &[u8]) -> String {
+                let err = Config::deserialize_iter_detailed(src, crate::encoding::Endian::Little)
+                    .unwrap_err();
+
+                format!("{err}")
+            }
+
+            let valid = [0xa5, 1, 0, 1, 2, 1, 2, 1, 2, 1, 2, 0];
+            assert!(Config::deserialize_iter(valid.iter()).is_ok());
+
+            let mut buf = valid;
+            buf[10] = 11;
+            assert_eq!(
+                "Config._channels[3]._gain at word 10: Validation",
+                describe(&buf)
+            );
+            assert!(matches!(
+                Config::deserialize_iter(buf.iter()),
+                Err(error::Error::Invalid)
+            ));
+
+            let mut buf = valid;
+            buf[5] = 2;
+            assert_eq!(
+                "Config._channels[1]._enabled at word 5: BadBool",
+                describe(&buf)
+            );
+
+            let mut buf = valid;
+            buf[11] = 7;
+            assert_eq!("Config._mode at word 11: UnknownTag", describe(&buf));
+
+            let mut buf = valid;
+            buf[11] = 1;
+            assert_eq!(
+                "Config._mode::_On.level at word 12: EndOfInput",
+                describe(&buf)
+            );
+
+            let mut buf = valid;
+            buf[0] = 0;
+            assert_eq!("Config at word 0: BadMagic", describe(&buf));
+        }
+
+        #[test]
+        fn serialized_len() {
+            #[derive(Debug, Clone, Copy, PartialEq, vanilla::SerializeIter)]
+            #[repr(u8)]
+            enum Mode {
+                Off,
+                On {
+                    level: u16,
+                },
+                Burst([u32; 2]),
+                #[cookie(fallback)]
+                Unknown(u8),
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter)]
+            #[cookie(magic = 0xa5, version = 2, align = 4)]
+            struct Frame {
+                count: u8,
+                #[cookie(len = count)]
+                items: [u16; 4],
+                modes: [Mode; 2],
+                #[cookie(scale = 0.5, repr = i8)]
+                gain: f32,
+                #[cookie(skip)]
+                _cache: u32,
+                #[cookie(until = 2)]
+                _legacy: u8,
+            }
+
+            fn written<T: SerializeIter>(value: &T) -> usize {
+                let mut buf = [0; 64];
+                let mut dst = buf.iter_mut();
+
+                value.serialize_iter(&mut dst).unwrap();
+
+                64 - dst.len()
+            }
+
+            assert_eq!(1, Mode::Off.serialized_len());
+            assert_eq!(3, Mode::On { level: 1 }.serialized_len());
+            assert_eq!(9, Mode::Burst([1, 2]).serialized_len());
+            assert_eq!(1, Mode::Unknown(7).serialized_len());
+
+            // 2 header words, 1 count, 2 per item, 1 gain, aligned to 4
+            for (count, modes, len) in [
+                (0, [Mode::Off, Mode::Off], 8),
+                (1, [Mode::Off, Mode::On { level: 3 }], 12),
+                (4, [Mode::Burst([1, 2]), Mode::Unknown(9)], 24),
+            ] {
+                let frame = Frame {
+                    count,
+                    items: [0x0102; 4],
+                    modes,
+                    gain: 1.5,
+                    _cache: 0,
+                    _legacy: 0,
+                };
+
+                assert_eq!(len, frame.serialized_len());
+                assert_eq!(written(&frame), frame.serialized_len());
+            }
+
+            let tuple = (7u8, [Mode::Off, Mode::On { level: 0 }], true);
+            assert_eq!(6, tuple.serialized_len());
+            assert_eq!(written(&tuple), tuple.serialized_len());
+        }
+
+        #[test]
+        fn decode() {
+            use core::task::Poll;
+
+            use cookie_cutter::encoding::vanilla::decode::{Decode, Decoder};
+
+            #[derive(Debug, Clone, Copy, PartialEq, vanilla::SerializeIter, vanilla::Decode)]
+            #[repr(u8)]
+            enum Mode {
+                Off,
+                On {
+                    level: u16,
+                },
+                #[cookie(fallback)]
+                Unknown(u8),
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::Decode)]
+            #[cookie(magic = 0xa5, version = 2, align = 4)]
+            struct Frame {
+                count: u8,
+                #[cookie(len = count)]
+                items: [u16; 3],
+                modes: [Mode; 2],
+                #[cookie(scale = 0.5, repr = i8, range = -8.0..=8.0)]
+                gain: f32,
+                #[cookie(skip)]
+                cache: u32,
+                #[cookie(since = 2, align = 2)]
+                crc: u16,
+            }
+
+            let frame = Frame {
+                count: 2,
+                items: [0x0102, 0x0304, 0],
+                modes: [Mode::On { level: 0x0506 }, Mode::Unknown(9)],
+                gain: 1.5,
+                cache: 0,
+                crc: 0x0708,
+            };
+
+            let mut buf = [0; 24];
+            frame.serialize_iter(buf.iter_mut()).unwrap();
+
+            let len = frame.serialized_len();
+            assert_eq!(16, len);
+
+            // words trickle in one at a time
+            let mut decoder = Decoder::<Frame>::new();
+
+            for word in &buf[..len - 1] {
+                assert!(decoder.feed([*word].iter()).is_pending());
+            }
+
+            assert!(matches!(
+                decoder.feed([buf[len - 1]].iter()),
+                Poll::Ready(Ok(value)) if value == frame
+            ));
+
+            // words following the value are kept
+            let mut src = buf[..len].iter().chain([0xee].iter());
+            assert!(decoder.feed(&mut src).is_ready());
+            assert_eq!(Some(&0xee), src.next());
+
+            // failures are reported as soon as they are received
+            let mut bad = buf;
+            bad[1] = 3;
+            assert!(matches!(decoder.feed(&bad[..2]), Poll::Ready(Err(_))));
+
+            let mut bad = buf;
+            bad[11] = 20;
+            assert!(decoder.feed(&bad[..11]).is_pending());
+            assert!(matches!(decoder.feed(&bad[11..]), Poll::Ready(Err(_))));
+
+            // older versions lack the checksum
+            let old = [0xa5, 1, 0, 0, 0, 3, 0, 0];
+            let mut decoder = Decoder::<Frame>::new();
+            assert!(decoder.feed(&old[..4]).is_pending());
+            assert!(matches!(
+                decoder.feed(&old[4..]),
+                Poll::Ready(Ok(Frame {
+                    modes: [Mode::Off, Mode::Off],
+                    gain: 1.5,
+                    crc: 0,
+                    ..
+                }))
+            ));
+
+            // only the fields of the deserialized variant are held
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::Decode)]
+            struct Payload([u32; 8]);
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::Decode)]
+            enum Wide<T: Decode> {
+                A(Payload),
+                B(Payload),
+                C(T),
+            }
+
+            assert!(
+                size_of::<<Wide<u8> as Decode>::State>()
+                    < 2 * size_of::<<Payload as Decode>::State>()
+            );
+
+            let wide = Wide::<u8>::B(Payload([1, 2, 3, 4, 5, 6, 7, 8]));
+            let mut buf = [0; 33];
+            wide.serialize_iter(buf.iter_mut()).unwrap();
+
+            let mut decoder = Decoder::<Wide<u8>>::new();
+            assert!(decoder.feed(&buf[..16]).is_pending());
+            assert!(matches!(
+                decoder.feed(&buf[16..]),
+                Poll::Ready(Ok(value)) if value == wide
+            ));
+        }
+
+        #[test]
+        fn schema() {
+            extern crate std;
+            use std::format;
+
+            use core::ops::Bound;
+
+            use cookie_cutter::schema::{Kind, Value};
+
+            #[derive(
+                Debug, Clone, Copy, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf,
+            )]
+            #[repr(u8)]
+            enum Mode {
+                Off,
+                On {
+                    level: u16,
+                },
+                #[cookie(fallback)]
+                Unknown(u8),
+            }
+
+            #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xa5, endian = "big", reserved = "zero")]
+            struct Frame {
+                id: u16,
+                mode: Mode,
+                #[cookie(scale = 0.5, repr = i8, range = -8.0..=8.0)]
+                gain: f32,
+                #[cookie(len = id, pad = 1)]
+                samples: [u8; 2],
+                #[cookie(skip)]
+                _cache: u32,
+            }
+
+            let Kind::Struct { container, fields } = Frame::SCHEMA.kind else {
+                panic!("Frame is a struct.");
+            };
+            assert_eq!(Frame::SCHEMA.size, 10);
+            assert_eq!(container.magic.unwrap().value, "0xa5");
+            assert!(container.zeroed);
+            assert_eq!(fields.len(), 4);
+            assert_eq!(fields[1].schema, Mode::SCHEMA);
+            assert_eq!(fields[2].scale, Some(0.5));
+            assert_eq!(fields[2].range.unwrap().text, "-8.0..=8.0");
+            assert_eq!(
+                fields[2].range.unwrap().bounds,
+                Some((
+                    Bound::Included(Value::Float(-8.0)),
+                    Bound::Included(Value::Float(8.0))
+                ))
+            );
+            assert_eq!(fields[3].len, Some("id"));
+
+            let Kind::Enum { variants, .. } = Mode::SCHEMA.kind else {
+                panic!("Mode is an enum.");
+            };
+            assert_eq!(variants[1].tag, Some(1));
+            assert_eq!(variants[2].tag, None);
+
+            assert_eq!(
+                format!("{}", Frame::SCHEMA),
+                "struct Frame (10 words), endian big, magic u8 = 0xa5, reserved zero {
+    id: u16,
+    mode: enum Mode (3 words), tag u8 {
+        Off = 0,
+        On = 1 {
+            level: u16,
+        },
+        Unknown = _,
+    },
+    gain: i8, scale 0.5, range -8.0..=8.0,
+    samples: [u8; 2], len id, pad 1,
+}"
+            );
+        }
+
+        #[test]
+        fn fingerprint() {
+            mod v1 {
+                use super::*;
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[repr(u8)]
+                pub enum Mode {
+                    Off,
+                    On(u16),
+                }
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[cookie(magic = 0xa5)]
+                pub struct Frame {
+                    pub id: u16,
+                    pub mode: Mode,
+                    #[cookie(len = id)]
+                    pub samples: [u8; 2],
+                }
+            }
+
+            // renamed, which does not change the wire
+            mod renamed {
+                use super::*;
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[repr(u8)]
+                pub enum State {
+                    Idle,
+                    Active(u16),
+                }
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[cookie(magic = 0xa5)]
+                pub struct Packet {
+                    pub count: u16,
+                    pub state: State,
+                    #[cookie(len = count)]
+                    pub data: [u8; 2],
+                }
+            }
+
+            mod retagged {
+                use super::*;
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[repr(u8)]
+                pub enum Mode {
+                    Off,
+                    On(u16) = 2,
+                }
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[cookie(magic = 0xa5)]
+                pub struct Frame {
+                    pub id: u16,
+                    pub mode: Mode,
+                    #[cookie(len = id)]
+                    pub samples: [u8; 2],
+                }
+            }
+
+            mod signed {
+                use super::*;
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[repr(u8)]
+                pub enum Mode {
+                    Off,
+                    On(u16),
+                }
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[cookie(magic = 0xa5)]
+                pub struct Frame {
+                    pub id: i16,
+                    pub mode: Mode,
+                    #[cookie(len = id)]
+                    pub samples: [u8; 2],
+                }
+            }
+
+            mod big {
+                use super::*;
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[repr(u8)]
+                pub enum Mode {
+                    Off,
+                    On(u16),
+                }
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[cookie(magic = 0xa5, endian = "big")]
+                pub struct Frame {
+                    pub id: u16,
+                    pub mode: Mode,
+                    #[cookie(len = id)]
+                    pub samples: [u8; 2],
+                }
+            }
+
+            // spelled differently, which does not change the wire
+            mod spelled {
+                use super::*;
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[repr(u8)]
+                pub enum Mode {
+                    Off,
+                    On(u16),
+                }
+
+                #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+                #[cookie(magic = 0xA5u8)]
+                pub struct Frame {
+                    pub id: u16,
+                    pub mode: Mode,
+                    #[cookie(len = id)]
+                    pub samples: [u8; 2],
+                }
+            }
+
+            const FINGERPRINT: u64 = v1::Frame::FINGERPRINT;
+
+            assert_eq!(FINGERPRINT, spelled::Frame::FINGERPRINT);
+            assert_eq!(FINGERPRINT, renamed::Packet::FINGERPRINT);
+            assert_ne!(FINGERPRINT, retagged::Frame::FINGERPRINT);
+            assert_ne!(FINGERPRINT, signed::Frame::FINGERPRINT);
+            assert_ne!(FINGERPRINT, big::Frame::FINGERPRINT);
+
+            assert_ne!(u16::FINGERPRINT, i16::FINGERPRINT);
+            assert_ne!(<[u8; 2]>::FINGERPRINT, u16::FINGERPRINT);
+            assert_ne!(<(u8, u16)>::FINGERPRINT, <(u16, u8)>::FINGERPRINT);
+            assert_ne!(f32::FINGERPRINT, u32::FINGERPRINT);
+        }
+
+        #[test]
+        fn fingerprint_values() {
+            // magic constants are hashed as the words they are serialized to
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xA55A)]
+            struct Upper(#[cookie(magic = 0x0F)] u8);
+
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xa55a)]
+            struct Lower(#[cookie(magic = 15)] u8);
+
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            #[cookie(magic = 0xa55b)]
+            struct Other(#[cookie(magic = 15)] u8);
+
+            assert_eq!(Upper::FINGERPRINT, Lower::FINGERPRINT);
+            assert_ne!(Upper::FINGERPRINT, Other::FINGERPRINT);
+
+            // ranges are hashed as their bounds
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Decimal(#[cookie(range = 0..=100)] u8);
+
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Hex(#[cookie(range = (0..=0x64))] u8);
+
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Exclusive(#[cookie(range = 0..100)] u8);
+
+            assert_eq!(Decimal::FINGERPRINT, Hex::FINGERPRINT);
+            assert_ne!(Decimal::FINGERPRINT, Exclusive::FINGERPRINT);
+        }
+
+        #[test]
+        fn fingerprint_with() {
+            use core::time::Duration;
+
+            // codecs of the same size
+            macro_rules! codec {
+                ($name:ident, $as:ident, $from:ident) => {
+                    mod $name {
+                        use core::time::Duration;
+
+                        use crate::{encoding::vanilla::with::Size, error, SerializeIter};
+
+                        // SAFETY: a `u32` is serialized
+                        pub const SIZE: Size = unsafe { Size::new(4) };
+
+                        pub fn serialize_iter<'a>(
+                            value: &Duration,
+                            dst: impl IntoIterator<Item = &'a mut u8>,
+                        ) -> Result<(), error::EndOfInput> {
+                            (value.$as() as u32).serialize_iter(dst)
+                        }
+
+                        pub fn deserialize_iter<'a>(
+                            src: impl IntoIterator<Item = &'a u8>,
+                        ) -> Result<Duration, error::Error> {
+                            Ok(Duration::$from(u32::deserialize_iter(src)?.into()))
+                        }
+
+                        pub fn serialized_len(_value: &Duration) -> usize {
+                            SIZE.get()
+                        }
+                    }
+                };
+            }
+
+            codec!(millis, as_millis, from_millis);
+            codec!(secs, as_secs, from_secs);
+
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Millis(#[cookie(with = millis)] Duration);
+
+            #[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+            struct Secs(#[cookie(with = secs)] Duration);
+
+            assert_ne!(Millis::FINGERPRINT, Secs::FINGERPRINT);
+        }
     }
 }
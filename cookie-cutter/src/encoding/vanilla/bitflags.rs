@@ -0,0 +1,222 @@
+//! Support for the flag types generated by the `bitflags` crate.
+//!
+//! Implementations are provided by the [`impl_bitflags!`](crate::impl_bitflags)
+//! macro, since the flag types are defined in downstream crates.
+
+#[doc(hidden)]
+pub use ::bitflags::Flags;
+
+use crate::error;
+
+/// Types implement this trait to define how bits
+/// that do not correspond to any defined flag are
+/// handled upon deserialization.
+pub trait UnknownBits {
+    fn from_bits<F: Flags>(bits: F::Bits) -> Result<F, error::Invalid>;
+}
+
+/// Unknown bits are rejected with [`error::Invalid`].
+pub struct Reject;
+
+impl UnknownBits for Reject {
+    fn from_bits<F: Flags>(bits: F::Bits) -> Result<F, error::Invalid> {
+        F::from_bits(bits).ok_or(error::Invalid)
+    }
+}
+
+/// Unknown bits are discarded.
+pub struct Truncate;
+
+impl UnknownBits for Truncate {
+    fn from_bits<F: Flags>(bits: F::Bits) -> Result<F, error::Invalid> {
+        Ok(F::from_bits_truncate(bits))
+    }
+}
+
+/// Unknown bits are kept as-is.
+pub struct Retain;
+
+impl UnknownBits for Retain {
+    fn from_bits<F: Flags>(bits: F::Bits) -> Result<F, error::Invalid> {
+        Ok(F::from_bits_retain(bits))
+    }
+}
+
+/// Implement `SerializeIter` and `SerializeBuf` for a `bitflags` type.
+///
+/// The flags are serialized as their underlying bits. The optional
+/// second argument is the [`UnknownBits`] policy used when
+/// deserializing, which defaults to [`Reject`].
+#[macro_export]
+macro_rules! impl_bitflags {
+    ($TYPE:ty) => {
+        $crate::impl_bitflags!($TYPE, $crate::encoding::vanilla::bitflags::Reject);
+    };
+    ($TYPE:ty, $POLICY:ty) => {
+        impl $crate::SerializeIter for $TYPE {
+            fn serialize_iter<'a>(
+                &self,
+                dst: impl IntoIterator<
+                    Item = &'a mut <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word,
+                >,
+            ) -> Result<(), $crate::error::EndOfInput>
+            where
+                <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word: 'a,
+            {
+                $crate::SerializeIter::serialize_iter(
+                    &$crate::encoding::vanilla::bitflags::Flags::bits(self),
+                    dst,
+                )
+            }
+
+            fn deserialize_iter<'a>(
+                src: impl IntoIterator<
+                    Item = &'a <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word,
+                >,
+            ) -> Result<Self, $crate::error::Error>
+            where
+                <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word: 'a,
+            {
+                let bits = <<$TYPE as $crate::encoding::vanilla::bitflags::Flags>::Bits as $crate::SerializeIter>::deserialize_iter(src)?;
+
+                Ok(<$POLICY as $crate::encoding::vanilla::bitflags::UnknownBits>::from_bits(bits)?)
+            }
+
+            fn serialized_len(&self) -> usize {
+                $crate::SerializeIter::serialized_len(
+                    &$crate::encoding::vanilla::bitflags::Flags::bits(self),
+                )
+            }
+
+            fn serialize_iter_endian<'a>(
+                &self,
+                dst: impl IntoIterator<
+                    Item = &'a mut <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word,
+                >,
+                endian: $crate::encoding::Endian,
+            ) -> Result<(), $crate::error::EndOfInput>
+            where
+                <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word: 'a,
+            {
+                $crate::SerializeIter::serialize_iter_endian(
+                    &$crate::encoding::vanilla::bitflags::Flags::bits(self),
+                    dst,
+                    endian,
+                )
+            }
+
+            fn deserialize_iter_endian<'a>(
+                src: impl IntoIterator<
+                    Item = &'a <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word,
+                >,
+                endian: $crate::encoding::Endian,
+            ) -> Result<Self, $crate::error::Error>
+            where
+                <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word: 'a,
+            {
+                let bits = <<$TYPE as $crate::encoding::vanilla::bitflags::Flags>::Bits as $crate::SerializeIter>::deserialize_iter_endian(src, endian)?;
+
+                Ok(<$POLICY as $crate::encoding::vanilla::bitflags::UnknownBits>::from_bits(bits)?)
+            }
+        }
+
+        // SAFETY: serialized exactly as the underlying bits
+        unsafe impl $crate::SerializeBuf for $TYPE {
+            type Serialized = <<$TYPE as $crate::encoding::vanilla::bitflags::Flags>::Bits as $crate::SerializeBuf>::Serialized;
+
+            // described as the underlying bits
+            const SCHEMA: &'static $crate::schema::Schema = &$crate::schema::Schema {
+                name: stringify!($TYPE),
+                ..*<<$TYPE as $crate::encoding::vanilla::bitflags::Flags>::Bits as $crate::SerializeBuf>::SCHEMA
+            };
+        }
+
+        impl $crate::encoding::vanilla::decode::Decode for $TYPE {
+            type State = <<$TYPE as $crate::encoding::vanilla::bitflags::Flags>::Bits as $crate::encoding::vanilla::decode::Decode>::State;
+
+            fn resume<'a>(
+                state: &mut Self::State,
+                src: &mut impl Iterator<
+                    Item = &'a <$crate::encoding::vanilla::Vanilla as $crate::encoding::Encoding>::Word,
+                >,
+                endian: $crate::encoding::Endian,
+            ) -> ::core::task::Poll<Result<Self, $crate::error::Invalid>> {
+                let bits = ::core::task::ready!(<<$TYPE as $crate::encoding::vanilla::bitflags::Flags>::Bits as $crate::encoding::vanilla::decode::Decode>::resume(state, src, endian))?;
+
+                ::core::task::Poll::Ready(<$POLICY as $crate::encoding::vanilla::bitflags::UnknownBits>::from_bits(bits))
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use bitflags::bitflags;
+
+    use super::{Retain, Truncate};
+    use crate::{error, SerializeBuf, SerializeIter};
+
+    bitflags! {
+        #[derive(Debug, PartialEq)]
+        struct Rejecting: u8 {
+            const A = 1 << 0;
+            const B = 1 << 1;
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Truncating: u16 {
+            const A = 1 << 0;
+            const B = 1 << 9;
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Retaining: u8 {
+            const A = 1 << 0;
+        }
+    }
+
+    crate::impl_bitflags!(Rejecting);
+    crate::impl_bitflags!(Truncating, Truncate);
+    crate::impl_bitflags!(Retaining, Retain);
+
+    #[test]
+    fn known() {
+        let mut buf = <Truncating as SerializeBuf>::Serialized::default();
+
+        let test_flags = Truncating::A | Truncating::B;
+        test_flags.serialize_buf(&mut buf);
+        assert_eq!([0x01, 0x02], buf);
+
+        assert_eq!(test_flags, Truncating::deserialize_buf(&buf).unwrap());
+
+        let buf = [0x03];
+        assert_eq!(
+            Rejecting::A | Rejecting::B,
+            Rejecting::deserialize_iter(buf.iter()).unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        let buf = [0x05];
+
+        match Rejecting::deserialize_iter(buf.iter()) {
+            Err(error::Error::Invalid) => {}
+            _ => panic!(),
+        }
+
+        let buf = [0xff, 0xff];
+        assert_eq!(
+            Truncating::A | Truncating::B,
+            Truncating::deserialize_buf(&buf).unwrap()
+        );
+
+        let buf = [0x81];
+        let read_flags = Retaining::deserialize_buf(&buf).unwrap();
+        assert_eq!(0x81, read_flags.bits());
+
+        let mut buf = [0];
+        read_flags.serialize_buf(&mut buf);
+        assert_eq!([0x81], buf);
+    }
+}
@@ -0,0 +1,437 @@
+//! Support for deserializing values received in pieces.
+//!
+//! [`Decoder`] is fed words as they arrive, keeping the progress
+//! of a partial value between calls rather than deserializing it
+//! again from the start, so no word is read twice.
+
+use core::{
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    task::{ready, Poll},
+};
+
+use super::{prefixed::Prefixed, Vanilla};
+
+use crate::{
+    encoding::{Encoding, Endian},
+    error, SerializeIter,
+};
+
+/// Types implement this trait to be deserialized
+/// incrementally, as by [`Decoder`].
+pub trait Decode: SerializeIter {
+    /// The progress of a partial deserialization.
+    type State: Default;
+
+    /// Resume deserializing from `state` with multi-word values in
+    /// the given order, consuming only as many words of `src` as
+    /// the value requires.
+    ///
+    /// Returns `Poll::Pending` once `src` is exhausted, the progress
+    /// being kept by `state`. Once ready, `state` must be reset
+    /// before deserializing another value.
+    fn resume<'a>(
+        state: &mut Self::State,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>>;
+}
+
+/// Deserializes values of `T` from words fed as they arrive.
+///
+/// ```
+/// # use core::task::Poll;
+/// # use cookie_cutter::encoding::vanilla::decode::Decoder;
+/// let mut decoder = Decoder::<u32>::new();
+///
+/// assert!(decoder.feed(&[0x78, 0x56]).is_pending());
+/// assert!(matches!(decoder.feed(&[0x34, 0x12]), Poll::Ready(Ok(0x1234_5678))));
+/// ```
+pub struct Decoder<T: Decode> {
+    state: T::State,
+    endian: Endian,
+}
+
+impl<T: Decode> Decoder<T> {
+    /// Create a decoder of little endian values, as `deserialize_iter`.
+    pub fn new() -> Self {
+        Self::with_endian(Endian::Little)
+    }
+
+    /// Create a decoder with multi-word values in the given order.
+    pub fn with_endian(endian: Endian) -> Self {
+        Self {
+            state: T::State::default(),
+            endian,
+        }
+    }
+
+    /// Consume words of `src` until a value is complete.
+    ///
+    /// Returns `Poll::Pending` once `src` is exhausted, in which case
+    /// the next call continues where this one stopped. Once ready,
+    /// the decoder starts over with the next value, so words following
+    /// the value are only kept if `src` is a mutable reference to an
+    /// iterator, i.e. `&mut words.iter()`.
+    pub fn feed<'a>(
+        &mut self,
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+    ) -> Poll<Result<T, error::Invalid>> {
+        let result = T::resume(&mut self.state, &mut src.into_iter(), self.endian);
+
+        if result.is_ready() {
+            self.reset();
+        }
+
+        result
+    }
+
+    /// Discard the progress of a partial value.
+    pub fn reset(&mut self) {
+        self.state = T::State::default();
+    }
+}
+
+impl<T: Decode> Default for Decoder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The words of a fixed-size value received so far.
+pub struct Words<const N: usize> {
+    words: [<Vanilla as Encoding>::Word; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for Words<N> {
+    fn default() -> Self {
+        Self {
+            words: [0; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Words<N> {
+    /// Receive the remaining words from `src`.
+    pub fn fill<'a>(
+        &mut self,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+    ) -> Poll<[<Vanilla as Encoding>::Word; N]> {
+        while self.len < N {
+            let Some(word) = src.next() else {
+                return Poll::Pending;
+            };
+
+            self.words[self.len] = *word;
+            self.len += 1;
+        }
+
+        Poll::Ready(self.words)
+    }
+}
+
+/// Consume `remaining` reserved words, which
+/// must be zero if `zero` is set.
+pub fn reserved<'a>(
+    remaining: &mut usize,
+    src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+    zero: bool,
+) -> Poll<Result<(), error::Invalid>> {
+    while *remaining > 0 {
+        let Some(word) = src.next() else {
+            return Poll::Pending;
+        };
+
+        *remaining -= 1;
+
+        if *word != 0 && zero {
+            return Poll::Ready(Err(error::Invalid));
+        }
+    }
+
+    Poll::Ready(Ok(()))
+}
+
+// number impls
+
+macro_rules! impl_number {
+    ($TYPE:ty, $SIZE:expr) => {
+        impl Decode for $TYPE {
+            type State = Words<$SIZE>;
+
+            fn resume<'a>(
+                state: &mut Self::State,
+                src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Poll<Result<Self, error::Invalid>> {
+                let bytes = ready!(state.fill(src));
+
+                Poll::Ready(Ok(match endian {
+                    Endian::Little => Self::from_le_bytes(bytes),
+                    Endian::Big => Self::from_be_bytes(bytes),
+                }))
+            }
+        }
+    };
+}
+
+impl_number!(u8, 1);
+impl_number!(u16, 2);
+impl_number!(u32, 4);
+impl_number!(u64, 8);
+impl_number!(i8, 1);
+impl_number!(i16, 2);
+impl_number!(i32, 4);
+impl_number!(i64, 8);
+impl_number!(f32, 4);
+impl_number!(f64, 8);
+
+// bool impls
+
+impl Decode for bool {
+    type State = Words<1>;
+
+    fn resume<'a>(
+        state: &mut Self::State,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        _endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>> {
+        match ready!(state.fill(src)) {
+            [0] => Poll::Ready(Ok(false)),
+            [1] => Poll::Ready(Ok(true)),
+            _ => Poll::Ready(Err(error::Invalid)),
+        }
+    }
+}
+
+// array impls
+
+/// The elements of an array received so far.
+pub struct Elements<T: Decode, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+    item: T::State,
+}
+
+impl<T: Decode, const N: usize> Default for Elements<T, N> {
+    fn default() -> Self {
+        Self {
+            // SAFETY: `items` is purely written to
+            items: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+            item: T::State::default(),
+        }
+    }
+}
+
+impl<T: Decode, const N: usize> Elements<T, N> {
+    /// Receive the remaining elements from `src`, up to `len` of them.
+    fn fill<'a>(
+        &mut self,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Poll<Result<(), error::Invalid>> {
+        while self.len < len {
+            let item = ready!(T::resume(&mut self.item, src, endian))?;
+
+            self.items[self.len].write(item);
+            self.len += 1;
+            self.item = T::State::default();
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: Decode, const N: usize> Drop for Elements<T, N> {
+    fn drop(&mut self) {
+        for item in &mut self.items[..self.len] {
+            // SAFETY: the first `len` elements are initialized
+            unsafe { item.assume_init_drop() };
+        }
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    type State = Elements<T, N>;
+
+    fn resume<'a>(
+        state: &mut Self::State,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>> {
+        ready!(state.fill(src, N, endian))?;
+
+        // the elements are moved out
+        state.len = 0;
+
+        // SAFETY: an array of `MaybeUninit` requires no initialization
+        let items = mem::replace(&mut state.items, unsafe {
+            MaybeUninit::uninit().assume_init()
+        });
+
+        // SAFETY: by now all elements are initialized
+        Poll::Ready(Ok(items.map(|e| unsafe { e.assume_init() })))
+    }
+}
+
+// tuple impls
+
+macro_rules! impl_tuple {
+    ( $(($TYPE:ident, $NAME:ident)),+ ) => {
+        impl<$($TYPE: Decode),+> Decode for ($($TYPE,)+) {
+            type State = ($((Option<$TYPE>, $TYPE::State),)+);
+
+            fn resume<'a>(
+                state: &mut Self::State,
+                src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Poll<Result<Self, error::Invalid>> {
+                let ($($NAME,)+) = state;
+
+                $(
+                    if $NAME.0.is_none() {
+                        $NAME.0 = Some(ready!($TYPE::resume(&mut $NAME.1, src, endian))?);
+                    }
+                )+
+
+                let ($(Some($NAME),)+) = ($($NAME.0.take(),)+) else {
+                    unreachable!("All elements are deserialized by now.");
+                };
+
+                Poll::Ready(Ok(($($NAME,)+)))
+            }
+        }
+    };
+}
+
+impl_tuple!((A, a));
+impl_tuple!((A, a), (B, b));
+impl_tuple!((A, a), (B, b), (C, c));
+impl_tuple!((A, a), (B, b), (C, c), (D, d));
+impl_tuple!((A, a), (B, b), (C, c), (D, d), (E, e));
+impl_tuple!((A, a), (B, b), (C, c), (D, d), (E, e), (F, f));
+impl_tuple!((A, a), (B, b), (C, c), (D, d), (E, e), (F, f), (G, g));
+
+// PhantomData impl (no-op)
+
+impl<T> Decode for PhantomData<T> {
+    type State = ();
+
+    fn resume<'a>(
+        _state: &mut Self::State,
+        _src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        _endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>> {
+        Poll::Ready(Ok(PhantomData))
+    }
+}
+
+// prefixed impls
+
+/// Types implement this trait to deserialize a variable
+/// number of elements incrementally, as by [`Decoder`].
+///
+/// Used by the `#[cookie(len = ...)]` field attribute.
+pub trait DecodePrefixed: Prefixed {
+    /// The progress of a partial deserialization.
+    type State: Default;
+
+    /// Resume deserializing `len` elements from `state`, as [`Decode::resume`].
+    ///
+    /// Fails with [`error::Invalid`] if `len` exceeds the capacity.
+    fn resume_prefixed<'a>(
+        state: &mut Self::State,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>>;
+}
+
+impl<T: Decode + Default, const N: usize> DecodePrefixed for [T; N] {
+    type State = Elements<T, N>;
+
+    fn resume_prefixed<'a>(
+        state: &mut Self::State,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>> {
+        if len > N {
+            return Poll::Ready(Err(error::Invalid));
+        }
+
+        ready!(state.fill(src, len, endian))?;
+
+        // the remainder is default initialized
+        while state.len < N {
+            state.items[state.len].write(T::default());
+            state.len += 1;
+        }
+
+        <[T; N]>::resume(state, src, endian)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<T: Decode, const N: usize> DecodePrefixed for heapless::Vec<T, N> {
+    type State = (Self, T::State);
+
+    fn resume_prefixed<'a>(
+        state: &mut Self::State,
+        src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Poll<Result<Self, error::Invalid>> {
+        if len > N {
+            return Poll::Ready(Err(error::Invalid));
+        }
+
+        let (items, item) = state;
+
+        while items.len() < len {
+            let value = ready!(T::resume(item, src, endian))?;
+
+            // capacity was checked above
+            let _ = items.push(value);
+            *item = T::State::default();
+        }
+
+        Poll::Ready(Ok(mem::take(items)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::task::Poll;
+
+    use super::Decoder;
+
+    #[test]
+    fn primitives() {
+        let mut decoder = Decoder::<(u8, [u16; 2], bool)>::new();
+
+        for chunk in [&[1][..], &[2], &[0, 3, 0], &[]] {
+            assert!(decoder.feed(chunk).is_pending());
+        }
+
+        let mut src = [1, 0xaa].iter();
+        assert!(matches!(
+            decoder.feed(&mut src),
+            Poll::Ready(Ok((1, [2, 3], true)))
+        ));
+        assert_eq!(Some(&0xaa), src.next());
+
+        assert!(decoder.feed(&[1, 0, 0, 0]).is_pending());
+        assert!(matches!(decoder.feed(&[0, 2]), Poll::Ready(Err(_))));
+
+        // the decoder starts over after failures
+        assert!(matches!(
+            decoder.feed(&[4, 5, 0, 6, 0, 0]),
+            Poll::Ready(Ok((4, [5, 6], false)))
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+//! Implementations for the fixed-point number types of the `fixed` crate.
+//!
+//! Fixed-point numbers are serialized as their underlying integer representation,
+//! so the number of fractional bits is a property of the type, not the wire.
+
+use fixed::{
+    types::extra::{LeEqU16, LeEqU32, LeEqU64, LeEqU8},
+    FixedI16, FixedI32, FixedI64, FixedI8, FixedU16, FixedU32, FixedU64, FixedU8,
+};
+
+use core::task::Poll;
+
+use super::{decode::Decode, Vanilla};
+
+use crate::{
+    encoding::{Encoding, Endian},
+    error,
+    schema::{Kind, Schema},
+    SerializeBuf, SerializeIter,
+};
+
+macro_rules! impl_fixed {
+    ($TYPE:ident, $BITS:ty, $FRAC:ident) => {
+        impl<Frac: $FRAC> SerializeIter for $TYPE<Frac> {
+            fn serialize_iter<'a, 'b>(
+                &'a self,
+                dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+            ) -> Result<(), error::EndOfInput>
+            where
+                <Vanilla as Encoding>::Word: 'b,
+            {
+                self.to_bits().serialize_iter(dst)
+            }
+
+            fn deserialize_iter<'a>(
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+            ) -> Result<Self, error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                // all bit patterns are valid
+                Ok(Self::from_bits(<$BITS>::deserialize_iter(src)?))
+            }
+
+            fn serialized_len(&self) -> usize {
+                self.to_bits().serialized_len()
+            }
+
+            fn serialize_iter_endian<'a, 'b>(
+                &'a self,
+                dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<(), error::EndOfInput>
+            where
+                <Vanilla as Encoding>::Word: 'b,
+            {
+                self.to_bits().serialize_iter_endian(dst, endian)
+            }
+
+            fn deserialize_iter_endian<'a>(
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<Self, error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                Ok(Self::from_bits(<$BITS>::deserialize_iter_endian(
+                    src, endian,
+                )?))
+            }
+        }
+
+        impl<Frac: $FRAC> Decode for $TYPE<Frac> {
+            type State = <$BITS as Decode>::State;
+
+            fn resume<'a>(
+                state: &mut Self::State,
+                src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Poll<Result<Self, error::Invalid>> {
+                <$BITS>::resume(state, src, endian).map_ok(Self::from_bits)
+            }
+        }
+
+        // SAFETY: serialized exactly as the underlying integer
+        unsafe impl<Frac: $FRAC> SerializeBuf for $TYPE<Frac> {
+            type Serialized = <$BITS as SerializeBuf>::Serialized;
+
+            const SCHEMA: &'static Schema = &Schema {
+                name: stringify!($TYPE),
+                size: <$BITS as SerializeBuf>::SCHEMA.size,
+                kind: Kind::Fixed {
+                    bits: <$BITS as SerializeBuf>::SCHEMA,
+                    frac: Frac::U32,
+                },
+            };
+        }
+    };
+}
+
+impl_fixed!(FixedU8, u8, LeEqU8);
+impl_fixed!(FixedU16, u16, LeEqU16);
+impl_fixed!(FixedU32, u32, LeEqU32);
+impl_fixed!(FixedU64, u64, LeEqU64);
+impl_fixed!(FixedI8, i8, LeEqU8);
+impl_fixed!(FixedI16, i16, LeEqU16);
+impl_fixed!(FixedI32, i32, LeEqU32);
+impl_fixed!(FixedI64, i64, LeEqU64);
+
+#[cfg(test)]
+mod tests {
+    use fixed::types::{I16F16, I1F15, U0F8, U48F16};
+
+    use crate::{medium::Medium, SerializeBuf, SerializeIter};
+
+    macro_rules! fixed_test {
+        ($TYPE:ty, $VALUE:expr, $SIZE:expr) => {
+            let mut buf = <$TYPE as SerializeBuf>::Serialized::default();
+            assert_eq!($SIZE, <<$TYPE as SerializeBuf>::Serialized as Medium>::SIZE);
+
+            let test_num = <$TYPE>::from_num($VALUE);
+
+            test_num.serialize_buf(&mut buf);
+            assert_eq!(test_num.to_le_bytes(), buf);
+
+            let read_num = <$TYPE>::deserialize_buf(&buf).unwrap();
+
+            assert_eq!(test_num, read_num);
+
+            let read_num = <$TYPE>::deserialize_iter(buf.iter()).unwrap();
+
+            assert_eq!(test_num, read_num);
+        };
+    }
+
+    #[test]
+    fn fixed() {
+        fixed_test!(U0F8, 0.5, 1);
+        fixed_test!(I1F15, -0.25, 2);
+        fixed_test!(I16F16, -1234.5678, 4);
+        fixed_test!(U48F16, 0xdead_beef_u32, 8);
+    }
+}
@@ -0,0 +1,184 @@
+//! Implementations for the half-precision float types of the `half` crate.
+
+use core::task::Poll;
+
+use half::{bf16, f16};
+
+use super::{decode::Decode, narrow::Narrow, Vanilla};
+
+use crate::{
+    encoding::{Encoding, Endian},
+    error,
+    schema::{FloatFormat, Kind, Schema},
+    SerializeBuf, SerializeIter,
+};
+
+macro_rules! impl_half {
+    ($TYPE:ty, $FORMAT:ident) => {
+        impl SerializeIter for $TYPE {
+            fn serialize_iter<'a, 'b>(
+                &'a self,
+                dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+            ) -> Result<(), error::EndOfInput>
+            where
+                <Vanilla as Encoding>::Word: 'b,
+            {
+                self.to_bits().serialize_iter(dst)
+            }
+
+            fn deserialize_iter<'a>(
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+            ) -> Result<Self, error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                // all bit patterns are valid
+                Ok(Self::from_bits(u16::deserialize_iter(src)?))
+            }
+
+            fn serialized_len(&self) -> usize {
+                self.to_bits().serialized_len()
+            }
+
+            fn serialize_iter_endian<'a, 'b>(
+                &'a self,
+                dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<(), error::EndOfInput>
+            where
+                <Vanilla as Encoding>::Word: 'b,
+            {
+                self.to_bits().serialize_iter_endian(dst, endian)
+            }
+
+            fn deserialize_iter_endian<'a>(
+                src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Result<Self, error::Error>
+            where
+                <Vanilla as Encoding>::Word: 'a,
+            {
+                Ok(Self::from_bits(u16::deserialize_iter_endian(src, endian)?))
+            }
+        }
+
+        // SAFETY: serialized exactly as the underlying bits
+        unsafe impl SerializeBuf for $TYPE {
+            type Serialized = <u16 as SerializeBuf>::Serialized;
+
+            const SCHEMA: &'static Schema = &Schema {
+                name: stringify!($TYPE),
+                size: 2,
+                kind: Kind::Float {
+                    format: FloatFormat::$FORMAT,
+                },
+            };
+        }
+
+        impl Decode for $TYPE {
+            type State = <u16 as Decode>::State;
+
+            fn resume<'a>(
+                state: &mut Self::State,
+                src: &mut impl Iterator<Item = &'a <Vanilla as Encoding>::Word>,
+                endian: Endian,
+            ) -> Poll<Result<Self, error::Invalid>> {
+                u16::resume(state, src, endian).map_ok(Self::from_bits)
+            }
+        }
+
+        impl Narrow<$TYPE> for f32 {
+            fn narrow(self) -> $TYPE {
+                <$TYPE>::from_f32(self)
+            }
+
+            fn widen(wire: $TYPE) -> Self {
+                wire.to_f32()
+            }
+        }
+
+        impl Narrow<$TYPE> for f64 {
+            fn narrow(self) -> $TYPE {
+                <$TYPE>::from_f64(self)
+            }
+
+            fn widen(wire: $TYPE) -> Self {
+                wire.to_f64()
+            }
+        }
+    };
+}
+
+impl_half!(f16, Binary16);
+impl_half!(bf16, BFloat16);
+
+#[cfg(test)]
+mod tests {
+    use half::{bf16, f16};
+
+    use crate as cookie_cutter; // for the proc macro
+    use cookie_cutter::{encoding::vanilla, SerializeBuf, SerializeIter};
+
+    macro_rules! half_test {
+        ($TYPE:ty) => {
+            let mut buf = <$TYPE as SerializeBuf>::Serialized::default();
+
+            for test_num in [<$TYPE>::from_f32(-1.5), <$TYPE>::MAX, <$TYPE>::INFINITY] {
+                test_num.serialize_buf(&mut buf);
+                assert_eq!(test_num.to_le_bytes(), buf);
+
+                let read_num = <$TYPE>::deserialize_buf(&buf).unwrap();
+
+                assert_eq!(test_num, read_num);
+
+                let read_num = <$TYPE>::deserialize_iter(buf.iter()).unwrap();
+
+                assert_eq!(test_num, read_num);
+            }
+
+            <$TYPE>::NAN.serialize_buf(&mut buf);
+            assert!(<$TYPE>::deserialize_buf(&buf).unwrap().is_nan());
+        };
+    }
+
+    #[test]
+    fn half() {
+        half_test!(f16);
+        half_test!(bf16);
+    }
+
+    #[test]
+    fn fingerprint() {
+        // of the same size, but not the same format
+        assert_ne!(f16::FINGERPRINT, bf16::FINGERPRINT);
+        assert_ne!(f16::FINGERPRINT, u16::FINGERPRINT);
+    }
+
+    #[test]
+    fn narrow() {
+        #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+        struct Foo {
+            #[cookie(repr = f16)]
+            a: f32,
+            #[cookie(repr = bf16)]
+            b: f64,
+        }
+
+        let mut buf = <Foo as SerializeBuf>::Serialized::default();
+        assert_eq!(4, buf.len());
+
+        // exactly representable
+        let test_foo = Foo { a: -1.5, b: 256.0 };
+        test_foo.serialize_buf(&mut buf);
+
+        assert_eq!(test_foo, Foo::deserialize_buf(&buf).unwrap());
+
+        // precision is lost
+        Foo { a: 0.1, b: 0.1 }.serialize_buf(&mut buf);
+
+        let read_foo = Foo::deserialize_buf(&buf).unwrap();
+
+        assert_eq!(f16::from_f32(0.1).to_f32(), read_foo.a);
+        assert_eq!(bf16::from_f64(0.1).to_f64(), read_foo.b);
+    }
+}
@@ -0,0 +1,24 @@
+//! Introspection of the layouts of derived types.
+//!
+//! The `SerializeBuf` derive generates `OFFSET_*` and `SIZE_*`
+//! constants for each field along with a `LAYOUT` table.
+//!
+//! `OFFSET_*` constants of fields which are not at a constant
+//! offset fail to compile when used.
+
+/// The location of a field within the serialized medium of a type.
+///
+/// Fields following a `#[cookie(len = ...)]` or versioned field, or a type
+/// which is not always serialized to its maximum size (e.g. an enum with
+/// variants of different sizes), are not at a constant offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FieldLayout {
+    /// The name of the field, prefixed with `Variant.` for enums.
+    pub name: &'static str,
+    /// The offset of the field from the start of the medium, in words,
+    /// or `None` if it varies with the values of earlier fields.
+    pub offset: Option<usize>,
+    /// The serialized size of the field, in words.
+    pub size: usize,
+}
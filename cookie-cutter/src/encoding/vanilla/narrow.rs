@@ -0,0 +1,24 @@
+//! Support for serializing values as a narrower type.
+//!
+//! Used by the `#[cookie(repr = ...)]` field attribute
+//! when no scale is specified.
+
+/// Types implement this trait to be representable
+/// by a narrower type `W`, potentially losing precision.
+pub trait Narrow<W>: Sized {
+    /// Convert to the nearest value of the narrow type.
+    fn narrow(self) -> W;
+
+    /// Convert from the narrow type.
+    fn widen(wire: W) -> Self;
+}
+
+impl Narrow<f32> for f64 {
+    fn narrow(self) -> f32 {
+        self as f32
+    }
+
+    fn widen(wire: f32) -> Self {
+        wire as Self
+    }
+}
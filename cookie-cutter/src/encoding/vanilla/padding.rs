@@ -0,0 +1,45 @@
+//! Support for reserved words within derived layouts.
+//!
+//! Used by the `#[cookie(pad = ...)]` and `#[cookie(align = ...)]` attributes.
+
+use super::Vanilla;
+
+use crate::{encoding::Encoding, error};
+
+/// The number of words required to advance
+/// `offset` to a multiple of `align`.
+pub const fn align(offset: usize, align: usize) -> usize {
+    (align - offset % align) % align
+}
+
+/// Zero-fill `len` reserved words.
+pub fn serialize<'a>(
+    dst: impl IntoIterator<Item = &'a mut <Vanilla as Encoding>::Word>,
+    len: usize,
+) -> Result<(), error::EndOfInput> {
+    let mut dst = dst.into_iter();
+
+    for _ in 0..len {
+        *dst.next().ok_or(error::EndOfInput)? = 0;
+    }
+
+    Ok(())
+}
+
+/// Consume `len` reserved words, which
+/// must be zero if `zero` is set.
+pub fn deserialize<'a>(
+    src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+    len: usize,
+    zero: bool,
+) -> Result<(), error::Error> {
+    let mut src = src.into_iter();
+
+    for _ in 0..len {
+        if *src.next().ok_or(error::EndOfInput)? != 0 && zero {
+            Err(error::Invalid)?;
+        }
+    }
+
+    Ok(())
+}
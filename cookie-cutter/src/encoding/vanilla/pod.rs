@@ -0,0 +1,66 @@
+//! Support for types which are serialized as their memory image.
+//!
+//! Used by the `#[cookie(pod)]` container attribute, with which
+//! `serialize_buf` and `deserialize_buf` copy the whole type at
+//! once on little endian targets.
+
+use core::{hint::unreachable_unchecked, mem::size_of};
+
+use crate::SerializeBuf;
+
+/// Types of which the serialization is identical to
+/// their memory image on little endian targets.
+///
+/// # Safety
+///
+/// The type must not contain padding, every bit pattern
+/// must be a valid value, and it must be serialized
+/// exactly as it is laid out in memory (little endian).
+pub unsafe trait Pod: SerializeBuf {}
+
+macro_rules! impl_pod {
+    ($($TYPE:ty),+) => {
+        $(
+            // SAFETY: numbers are serialized as their little endian bytes
+            unsafe impl Pod for $TYPE {}
+        )+
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+// SAFETY: elements are contiguous and serialized in order
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Serialize `value` to `dst`, with a single copy
+/// on little endian targets.
+pub fn serialize<T: Pod, const N: usize>(value: &T, dst: &mut [u8; N]) {
+    const { assert!(N == size_of::<T>()) };
+
+    if cfg!(target_endian = "little") {
+        // SAFETY: `T` is its own serialization, of `N` words
+        unsafe {
+            core::ptr::copy_nonoverlapping(value as *const T as *const u8, dst.as_mut_ptr(), N)
+        };
+    } else {
+        // SAFETY: the serialized size of `T` is `N`
+        unsafe { value.serialize_iter(dst.iter_mut()).unwrap_unchecked() };
+    }
+}
+
+/// Deserialize a value from `src`, with a single copy
+/// on little endian targets.
+pub fn deserialize<T: Pod, const N: usize>(src: &[u8; N]) -> T {
+    const { assert!(N == size_of::<T>()) };
+
+    if cfg!(target_endian = "little") {
+        // SAFETY: `src` is the memory image of a `T`, any of which is valid
+        unsafe { core::ptr::read_unaligned(src.as_ptr() as *const T) }
+    } else {
+        match T::deserialize_iter(src.iter()) {
+            Ok(value) => value,
+            // SAFETY: `src` holds a full serialization, any of which is valid
+            Err(_) => unsafe { unreachable_unchecked() },
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Support for collections of which only a prefix is serialized.
+//!
+//! Used by the `#[cookie(len = ...)]` field attribute, where the
+//! number of elements is held by an earlier field.
+
+use core::mem::MaybeUninit;
+
+use super::Vanilla;
+
+use crate::{
+    encoding::{Encoding, Endian},
+    error,
+    medium::{Medium, Repeat},
+    schema::Schema,
+    SerializeBuf, SerializeIter,
+};
+
+#[cfg(feature = "heapless")]
+use crate::schema::Kind;
+
+/// The number of elements given by a `len` field, or
+/// `None` if it is negative or exceeds `usize`.
+///
+/// Upon serialization, such counts select no elements,
+/// and upon deserialization they are rejected.
+pub fn count<C: TryInto<usize>>(len: C) -> Option<usize> {
+    len.try_into().ok()
+}
+
+/// Types implement this trait to serialize
+/// a variable number of elements, up to a capacity.
+pub trait Prefixed: Sized {
+    /// Serialize the first `len` elements.
+    ///
+    /// It is up to the caller to ensure `len` does
+    /// not exceed the number of elements present,
+    /// any excess is ignored.
+    fn serialize_iter_prefixed<'a, 'b>(
+        &'a self,
+        dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Result<(), error::EndOfInput>
+    where
+        <Vanilla as Encoding>::Word: 'b;
+
+    /// Deserialize `len` elements.
+    ///
+    /// Fails with [`error::Invalid`] if `len` exceeds the capacity.
+    fn deserialize_iter_prefixed<'a>(
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Result<Self, error::Error>
+    where
+        <Vanilla as Encoding>::Word: 'a;
+
+    /// The number of words serializing
+    /// the first `len` elements writes.
+    fn serialized_len_prefixed(&self, len: usize) -> usize;
+}
+
+/// Types implement this trait to provide a medium
+/// large enough for the maximum number of elements.
+///
+/// # Safety
+///
+/// `Serialized` must be large enough to hold
+/// a full-capacity serialization.
+pub unsafe trait PrefixedBuf: Prefixed {
+    type Serialized: Medium;
+
+    /// The description of the serialized layout
+    /// of a full-capacity serialization.
+    ///
+    /// Unless implemented, the layout is opaque.
+    const SCHEMA: &'static Schema = &Schema::opaque(<Self::Serialized as Medium>::SIZE);
+}
+
+/// Deserialize `len` elements into an array,
+/// default initializing the remainder.
+fn deserialize_array<'a, T: SerializeIter + Default, const N: usize>(
+    src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+    len: usize,
+    endian: Endian,
+) -> Result<[T; N], error::Error> {
+    if len > N {
+        Err(error::Invalid)?;
+    }
+
+    let mut src = src.into_iter();
+
+    // SAFETY: `result` is purely written to
+    let mut result: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
+
+    for (i, value) in result.iter_mut().enumerate() {
+        if i < len {
+            value.write(T::deserialize_iter_endian(&mut src, endian)?);
+        } else {
+            value.write(T::default());
+        }
+    }
+
+    // SAFETY: by now all elements are initialized
+    Ok(result.map(|e| unsafe { e.assume_init() }))
+}
+
+impl<T: SerializeIter + Default, const N: usize> Prefixed for [T; N] {
+    fn serialize_iter_prefixed<'a, 'b>(
+        &'a self,
+        dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Result<(), error::EndOfInput>
+    where
+        <Vanilla as Encoding>::Word: 'b,
+    {
+        let mut dst = dst.into_iter();
+
+        for item in self.iter().take(len) {
+            item.serialize_iter_endian(&mut dst, endian)?;
+        }
+
+        Ok(())
+    }
+
+    fn deserialize_iter_prefixed<'a>(
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Result<Self, error::Error>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        deserialize_array(src, len, endian)
+    }
+
+    fn serialized_len_prefixed(&self, len: usize) -> usize {
+        self.iter()
+            .take(len)
+            .map(SerializeIter::serialized_len)
+            .sum()
+    }
+}
+
+// SAFETY: at most `N` elements are serialized
+unsafe impl<T: SerializeBuf + Default, const N: usize> PrefixedBuf for [T; N] {
+    type Serialized = Repeat<T::Serialized, N>;
+
+    const SCHEMA: &'static Schema = <Self as SerializeBuf>::SCHEMA;
+}
+
+#[cfg(feature = "heapless")]
+impl<T: SerializeIter, const N: usize> Prefixed for heapless::Vec<T, N> {
+    fn serialize_iter_prefixed<'a, 'b>(
+        &'a self,
+        dst: impl IntoIterator<Item = &'b mut <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Result<(), error::EndOfInput>
+    where
+        <Vanilla as Encoding>::Word: 'b,
+    {
+        let mut dst = dst.into_iter();
+
+        for item in self.iter().take(len) {
+            item.serialize_iter_endian(&mut dst, endian)?;
+        }
+
+        Ok(())
+    }
+
+    fn deserialize_iter_prefixed<'a>(
+        src: impl IntoIterator<Item = &'a <Vanilla as Encoding>::Word>,
+        len: usize,
+        endian: Endian,
+    ) -> Result<Self, error::Error>
+    where
+        <Vanilla as Encoding>::Word: 'a,
+    {
+        if len > N {
+            Err(error::Invalid)?;
+        }
+
+        let mut src = src.into_iter();
+        let mut result = Self::new();
+
+        for _ in 0..len {
+            // capacity was checked above
+            let _ = result.push(T::deserialize_iter_endian(&mut src, endian)?);
+        }
+
+        Ok(result)
+    }
+
+    fn serialized_len_prefixed(&self, len: usize) -> usize {
+        self.iter()
+            .take(len)
+            .map(SerializeIter::serialized_len)
+            .sum()
+    }
+}
+
+// SAFETY: at most `N` elements are serialized
+#[cfg(feature = "heapless")]
+unsafe impl<T: SerializeBuf, const N: usize> PrefixedBuf for heapless::Vec<T, N> {
+    type Serialized = Repeat<T::Serialized, N>;
+
+    const SCHEMA: &'static Schema = &Schema {
+        name: "heapless::Vec",
+        size: <Self::Serialized as Medium>::SIZE,
+        kind: Kind::Array {
+            len: N,
+            item: T::SCHEMA,
+        },
+    };
+}
+
+#[cfg(all(test, feature = "heapless"))]
+mod tests {
+    use crate as cookie_cutter; // for the proc macro
+    use cookie_cutter::{encoding::vanilla, error, SerializeBuf, SerializeIter};
+
+    #[test]
+    fn heapless() {
+        #[derive(Debug, PartialEq, vanilla::SerializeIter, vanilla::SerializeBuf)]
+        struct Foo {
+            count: u16,
+            #[cookie(len = count)]
+            items: heapless::Vec<u8, 3>,
+        }
+
+        let mut buf = <Foo as SerializeBuf>::Serialized::default();
+        assert_eq!(5, buf.len());
+
+        let test_foo = Foo {
+            count: 2,
+            items: heapless::Vec::from_slice(&[0xa, 0xb]).unwrap(),
+        };
+        test_foo.serialize_buf(&mut buf);
+
+        assert_eq!([2, 0, 0xa, 0xb, 0], buf);
+        assert_eq!(test_foo, Foo::deserialize_buf(&buf).unwrap());
+
+        assert!(matches!(
+            Foo::deserialize_iter([4, 0, 1, 2, 3, 4].iter()),
+            Err(error::Error::Invalid)
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+//! Support for serializing floating point values as scaled integers.
+//!
+//! Used by the `#[cookie(scale = ..., repr = ...)]` field attribute.
+
+/// Types implement this trait to be representable
+/// as an integer multiple of a scale factor.
+pub trait Scale<R>: Sized {
+    /// Convert to the nearest multiple of `scale`,
+    /// rounding half-way values away from zero.
+    ///
+    /// Values outside of the range of `R` saturate
+    /// and `NaN` becomes zero.
+    fn to_scaled(self, scale: Self) -> R;
+
+    /// Convert from a multiple of `scale`.
+    fn from_scaled(repr: R, scale: Self) -> Self;
+}
+
+macro_rules! impl_scale {
+    ($FLOAT:ty, $ROUND:path, $($REPR:ty),+) => {
+        $(
+            impl Scale<$REPR> for $FLOAT {
+                fn to_scaled(self, scale: Self) -> $REPR {
+                    // rounds half-way values away from zero
+                    let value = $ROUND(self / scale);
+
+                    // the bounds are exact or rounded up as floats,
+                    // so values within them are exact as integers
+                    if value.is_nan() {
+                        0
+                    } else if value <= <$REPR>::MIN as Self {
+                        <$REPR>::MIN
+                    } else if value >= <$REPR>::MAX as Self {
+                        <$REPR>::MAX
+                    } else {
+                        value as $REPR
+                    }
+                }
+
+                fn from_scaled(repr: $REPR, scale: Self) -> Self {
+                    repr as Self * scale
+                }
+            }
+        )+
+    };
+}
+
+impl_scale!(f32, libm::roundf, u8, u16, u32, u64, i8, i16, i32, i64);
+impl_scale!(f64, libm::round, u8, u16, u32, u64, i8, i16, i32, i64);
+
+#[cfg(test)]
+mod tests {
+    use super::Scale;
+
+    #[test]
+    fn round() {
+        // the largest value below one half
+        assert_eq!(0i8, Scale::<i8>::to_scaled(0.49999997f32, 1.0));
+        assert_eq!(0i8, Scale::<i8>::to_scaled(-0.49999997f32, 1.0));
+        assert_eq!(0i8, Scale::<i8>::to_scaled(0.49999999999999994f64, 1.0));
+
+        // half-way values are rounded away from zero
+        assert_eq!(1i8, Scale::<i8>::to_scaled(0.5f32, 1.0));
+        assert_eq!(-1i8, Scale::<i8>::to_scaled(-0.5f32, 1.0));
+        assert_eq!(3i8, Scale::<i8>::to_scaled(2.5f64, 1.0));
+        assert_eq!(-3i8, Scale::<i8>::to_scaled(-2.5f64, 1.0));
+    }
+
+    #[test]
+    fn saturate() {
+        assert_eq!(i8::MAX, Scale::<i8>::to_scaled(127.4f32, 1.0));
+        assert_eq!(i8::MAX, Scale::<i8>::to_scaled(127.5f32, 1.0));
+        assert_eq!(i8::MIN, Scale::<i8>::to_scaled(-128.5f32, 1.0));
+        assert_eq!(u8::MIN, Scale::<u8>::to_scaled(-0.6f64, 1.0));
+        assert_eq!(u8::MAX, Scale::<u8>::to_scaled(1e9f64, 1.0));
+
+        // the bounds of wide integers are not exact as floats
+        assert_eq!(i32::MAX, Scale::<i32>::to_scaled(i32::MAX as f32, 1.0));
+        assert_eq!(i32::MIN, Scale::<i32>::to_scaled(i32::MIN as f32, 1.0));
+        assert_eq!(u64::MAX, Scale::<u64>::to_scaled(f64::INFINITY, 1.0));
+        assert_eq!(i64::MIN, Scale::<i64>::to_scaled(f64::NEG_INFINITY, 1.0));
+
+        assert_eq!(0i16, Scale::<i16>::to_scaled(f32::NAN, 1.0));
+    }
+}
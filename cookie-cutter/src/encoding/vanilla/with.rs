@@ -0,0 +1,28 @@
+//! Support for fields serialized by the functions of a module.
+//!
+//! Used by the `#[cookie(with = ...)]` field attribute, of which
+//! the module provides `pub const SIZE: Size` alongside its functions.
+
+/// The maximum number of words the `serialize_iter` function of
+/// a module writes, and its `deserialize_iter` function reads.
+///
+/// `SerializeBuf` sizes mediums by it, so it can only be
+/// constructed unsafely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size(usize);
+
+impl Size {
+    /// # Safety
+    ///
+    /// `serialize_iter` must write at most `size` words for any value,
+    /// and `deserialize_iter` must read at most `size` words.
+    /// Otherwise `SerializeBuf` methods result in UB.
+    pub const unsafe fn new(size: usize) -> Self {
+        Self(size)
+    }
+
+    /// The number of words.
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
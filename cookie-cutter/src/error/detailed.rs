@@ -0,0 +1,222 @@
+//! Errors locating the failure within the deserialized value.
+//!
+//! These are considerably larger than [`Error`], hence opt-in.
+
+use core::fmt;
+
+use super::{EndOfInput, Error, Invalid};
+
+/// The maximum number of segments of the path to the failing field,
+/// beyond which the outermost segments are omitted.
+pub const DEPTH: usize = 6;
+
+/// Why deserialization failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Reason {
+    /// The serialization medium ended prematurely.
+    EndOfInput,
+    /// A `bool` was neither 0 nor 1.
+    BadBool,
+    /// An enum tag matched no variant.
+    UnknownTag,
+    /// A constant did not match.
+    BadMagic,
+    /// The version was newer than the current one.
+    UnknownVersion,
+    /// A value was outside of its range or rejected by its `validate` function.
+    Validation,
+    /// The value was invalid otherwise.
+    Invalid,
+}
+
+/// A step along the path to the failing field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Segment {
+    /// A field of a struct or variant, by name or index.
+    Field(&'static str),
+    /// An enum variant.
+    Variant(&'static str),
+    /// An element of an array.
+    Index(usize),
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field(name) => write!(f, ".{name}"),
+            Self::Variant(name) => write!(f, "::{name}"),
+            Self::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+/// A deserialization error with the word offset,
+/// path and reason of the failure.
+///
+/// Displayed as e.g. `Config.channels[3].gain at word 42: Validation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detailed {
+    /// The offset of the failing field from the start of the medium, in words.
+    pub offset: usize,
+    /// Why deserialization failed.
+    pub reason: Reason,
+    /// The outermost type the failure is located within.
+    ty: Option<&'static str>,
+    /// Segments from the failing field outwards.
+    segments: [Segment; DEPTH],
+    depth: usize,
+    /// Whether outer segments were omitted.
+    truncated: bool,
+}
+
+impl Detailed {
+    pub const fn new(reason: Reason) -> Self {
+        Self {
+            offset: 0,
+            reason,
+            ty: None,
+            segments: [Segment::Index(0); DEPTH],
+            depth: 0,
+            truncated: false,
+        }
+    }
+
+    /// Locate the error within `segment`, which
+    /// starts `offset` words into the enclosing value.
+    pub fn within(mut self, segment: Segment, offset: usize) -> Self {
+        self.offset += offset;
+
+        if self.depth < DEPTH {
+            self.segments[self.depth] = segment;
+            self.depth += 1;
+        } else {
+            self.truncated = true;
+        }
+
+        self
+    }
+
+    /// Locate the error within a value of type `ty`.
+    pub fn of(mut self, ty: &'static str) -> Self {
+        self.ty = Some(ty);
+
+        self
+    }
+
+    /// The outermost type the failure is located within, if known.
+    pub fn ty(&self) -> Option<&'static str> {
+        self.ty
+    }
+
+    /// The path to the failing field, from the outermost segment.
+    pub fn path(&self) -> impl Iterator<Item = &Segment> {
+        self.segments[..self.depth].iter().rev()
+    }
+}
+
+impl fmt::Display for Detailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.truncated {
+            write!(f, "..")?;
+        }
+
+        if let Some(ty) = self.ty {
+            write!(f, "{ty}")?;
+        }
+
+        for segment in self.path() {
+            write!(f, "{segment}")?;
+        }
+
+        write!(f, " at word {}: {:?}", self.offset, self.reason)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Detailed {
+    fn format(&self, f: defmt::Formatter) {
+        if self.truncated {
+            defmt::write!(f, "..");
+        }
+
+        if let Some(ty) = self.ty {
+            defmt::write!(f, "{=str}", ty);
+        }
+
+        for segment in self.path() {
+            match segment {
+                Segment::Field(name) => defmt::write!(f, ".{=str}", name),
+                Segment::Variant(name) => defmt::write!(f, "::{=str}", name),
+                Segment::Index(index) => defmt::write!(f, "[{=usize}]", index),
+            }
+        }
+
+        defmt::write!(f, " at word {=usize}: {}", self.offset, self.reason);
+    }
+}
+
+impl From<Reason> for Detailed {
+    fn from(reason: Reason) -> Self {
+        Self::new(reason)
+    }
+}
+
+impl From<Error> for Detailed {
+    fn from(err: Error) -> Self {
+        Self::new(match err {
+            Error::EndOfInput => Reason::EndOfInput,
+            Error::Invalid => Reason::Invalid,
+        })
+    }
+}
+
+impl From<EndOfInput> for Detailed {
+    fn from(_: EndOfInput) -> Self {
+        Self::new(Reason::EndOfInput)
+    }
+}
+
+impl From<Invalid> for Detailed {
+    fn from(_: Invalid) -> Self {
+        Self::new(Reason::Invalid)
+    }
+}
+
+impl From<Reason> for Error {
+    fn from(reason: Reason) -> Self {
+        match reason {
+            Reason::EndOfInput => Self::EndOfInput,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+impl From<Detailed> for Error {
+    fn from(err: Detailed) -> Self {
+        err.reason.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern crate std;
+    use std::format;
+
+    #[test]
+    fn display() {
+        let err = Detailed::new(Reason::Validation)
+            .within(Segment::Field("gain"), 2)
+            .within(Segment::Index(3), 12)
+            .within(Segment::Field("channels"), 4)
+            .of("Config");
+
+        assert_eq!(
+            "Config.channels[3].gain at word 18: Validation",
+            format!("{err}")
+        );
+    }
+}
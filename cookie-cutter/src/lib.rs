@@ -4,11 +4,13 @@
 
 pub mod encoding;
 pub mod medium;
+pub mod schema;
 
 use core::hint::unreachable_unchecked;
 
-use encoding::{vanilla::Vanilla, Encoding};
+use encoding::{vanilla::Vanilla, Encoding, Endian};
 use medium::Medium;
+use schema::Schema;
 
 pub mod error {
     #[derive(Debug, Clone, Copy)]
@@ -37,6 +39,12 @@ pub mod error {
             Self::Invalid
         }
     }
+
+    #[cfg(feature = "detailed-errors")]
+    mod detailed;
+
+    #[cfg(feature = "detailed-errors")]
+    pub use detailed::{Detailed, Reason, Segment, DEPTH};
 }
 
 /// This trait defines a highly adaptable interface
@@ -59,6 +67,113 @@ pub trait SerializeIter<E: Encoding = Vanilla>: Sized {
     ) -> Result<Self, error::Error>
     where
         E::Word: 'a;
+
+    /// The number of words serializing this
+    /// value writes to a serialization medium.
+    ///
+    /// There is no default, as counting would require a medium of the
+    /// encoding's words, so implementations written before 0.2.0 must
+    /// add it, usually as the sum of the lengths of their constituents.
+    /// Derived implementations provide it.
+    fn serialized_len(&self) -> usize;
+
+    /// Serialize the implementer type to a
+    /// serialization medium via an iterator,
+    /// with multi-word values in the given order.
+    ///
+    /// Unless implemented, the order is ignored.
+    fn serialize_iter_endian<'a>(
+        &self,
+        dst: impl IntoIterator<Item = &'a mut E::Word>,
+        endian: Endian,
+    ) -> Result<(), error::EndOfInput>
+    where
+        E::Word: 'a,
+    {
+        let _ = endian;
+
+        self.serialize_iter(dst)
+    }
+
+    /// Deserialize the implementer type from a
+    /// serialization medium via an iterator,
+    /// with multi-word values in the given order.
+    ///
+    /// Unless implemented, the order is ignored.
+    fn deserialize_iter_endian<'a>(
+        src: impl IntoIterator<Item = &'a E::Word>,
+        endian: Endian,
+    ) -> Result<Self, error::Error>
+    where
+        E::Word: 'a,
+    {
+        let _ = endian;
+
+        Self::deserialize_iter(src)
+    }
+
+    /// Deserialize the implementer type from a
+    /// serialization medium via an iterator,
+    /// with multi-word values in the given order,
+    /// locating any failure within the value.
+    ///
+    /// Unless implemented, failures are located
+    /// at the start of the value.
+    #[cfg(feature = "detailed-errors")]
+    #[allow(clippy::result_large_err)]
+    fn deserialize_iter_detailed<'a>(
+        src: impl IntoIterator<Item = &'a E::Word>,
+        endian: Endian,
+    ) -> Result<Self, error::Detailed>
+    where
+        E::Word: 'a,
+    {
+        Ok(Self::deserialize_iter_endian(src, endian)?)
+    }
+
+    /// Deserialize the implementer type from a
+    /// serialization medium via an iterator,
+    /// overwriting `self` rather than returning
+    /// a new value.
+    ///
+    /// Upon failure, `self` may be partially overwritten.
+    ///
+    /// Unless implemented, the value is deserialized
+    /// by value and moved into `self`.
+    fn deserialize_in_place<'a>(
+        &mut self,
+        src: impl IntoIterator<Item = &'a E::Word>,
+    ) -> Result<(), error::Error>
+    where
+        E::Word: 'a,
+    {
+        *self = Self::deserialize_iter(src)?;
+
+        Ok(())
+    }
+
+    /// Deserialize the implementer type from a
+    /// serialization medium via an iterator,
+    /// with multi-word values in the given order,
+    /// overwriting `self` rather than returning
+    /// a new value.
+    ///
+    /// Upon failure, `self` may be partially overwritten.
+    ///
+    /// Unless implemented, the value is deserialized
+    /// by value and moved into `self`.
+    fn deserialize_in_place_endian<'a>(
+        &mut self,
+        src: impl IntoIterator<Item = &'a E::Word>,
+        endian: Endian,
+    ) -> Result<(), error::Error>
+    where
+        E::Word: 'a,
+    {
+        *self = Self::deserialize_iter_endian(src, endian)?;
+
+        Ok(())
+    }
 }
 
 /// This trait defines a more rigid/static serialization
@@ -82,6 +197,17 @@ pub unsafe trait SerializeBuf<E: Encoding = Vanilla>: SerializeIter<E> {
     /// The type respresenting the serialized form of the implementer type.
     type Serialized: Medium<E>;
 
+    /// The description of the serialized layout of the implementer type.
+    ///
+    /// Unless implemented, the layout is opaque.
+    const SCHEMA: &'static Schema = &Schema::opaque(<Self::Serialized as Medium<E>>::SIZE);
+
+    /// A hash of `SCHEMA`, which peers may compare to refuse
+    /// serialized values of incompatible layouts.
+    ///
+    /// Types without a schema are only distinguished by their size.
+    const FINGERPRINT: u64 = Self::SCHEMA.fingerprint();
+
     /// Serialize the implementer type to a
     /// serialization medium.
     fn serialize_buf(&self, dest: &mut Self::Serialized) {
@@ -100,4 +226,14 @@ pub unsafe trait SerializeBuf<E: Encoding = Vanilla>: SerializeIter<E> {
             error::Error::EndOfInput => unsafe { unreachable_unchecked() },
         })
     }
+
+    /// Deserialize the implementer type from a
+    /// serialization medium, locating any failure
+    /// within the value.
+    #[cfg(feature = "detailed-errors")]
+    #[allow(clippy::result_large_err)]
+    fn deserialize_buf_detailed(src: &Self::Serialized) -> Result<Self, error::Detailed> {
+        // as `deserialize_iter`, which defaults to little endian
+        SerializeIter::deserialize_iter_detailed(src.get_iter(), Endian::Little)
+    }
 }
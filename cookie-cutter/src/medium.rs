@@ -1,3 +1,5 @@
+use core::mem::{size_of, ManuallyDrop};
+
 use crate::encoding::{vanilla::Vanilla, Encoding};
 
 // TODO: iters should be associated types defined
@@ -34,3 +36,136 @@ impl<E: Encoding, const N: usize> Medium<E> for [E::Word; N] {
         self.iter_mut()
     }
 }
+
+// Composite mediums.
+//
+// These allow the serialized form of generic types
+// to be expressed in terms of the serialized forms
+// of their constituents, since computing array lengths
+// from generic parameters requires the "generic_const_exprs" feature.
+
+/// A medium consisting of two mediums, one after the other.
+#[derive(Default)]
+pub struct Concat<A, B>(pub A, pub B);
+
+impl<E: Encoding, A: Medium<E>, B: Medium<E>> Medium<E> for Concat<A, B> {
+    const SIZE: usize = A::SIZE + B::SIZE;
+
+    fn get_iter<'a>(&'a self) -> impl Iterator<Item = &'a E::Word>
+    where
+        E::Word: 'a,
+    {
+        self.0.get_iter().chain(self.1.get_iter())
+    }
+
+    fn get_iter_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut E::Word>
+    where
+        E::Word: 'a,
+    {
+        self.0.get_iter_mut().chain(self.1.get_iter_mut())
+    }
+}
+
+/// A medium large enough to hold either of two mediums.
+///
+/// Only the medium with the larger footprint is ever
+/// initialized and accessed.
+pub union Union<A, B> {
+    a: ManuallyDrop<A>,
+    b: ManuallyDrop<B>,
+}
+
+impl<A, B> Union<A, B> {
+    const A_IS_LARGER: bool = size_of::<A>() >= size_of::<B>();
+}
+
+impl<A: Default, B: Default> Default for Union<A, B> {
+    fn default() -> Self {
+        if Self::A_IS_LARGER {
+            Self {
+                a: ManuallyDrop::new(A::default()),
+            }
+        } else {
+            Self {
+                b: ManuallyDrop::new(B::default()),
+            }
+        }
+    }
+}
+
+impl<E: Encoding, A: Medium<E>, B: Medium<E>> Medium<E> for Union<A, B> {
+    const SIZE: usize = if A::SIZE >= B::SIZE { A::SIZE } else { B::SIZE };
+
+    fn get_iter<'a>(&'a self) -> impl Iterator<Item = &'a E::Word>
+    where
+        E::Word: 'a,
+    {
+        const {
+            assert!(
+                (Self::A_IS_LARGER && A::SIZE == Self::SIZE)
+                    || (!Self::A_IS_LARGER && B::SIZE == Self::SIZE),
+                "The medium with the larger footprint must also be the larger medium."
+            )
+        };
+
+        // SAFETY: the larger medium is the one initialized
+        // upon construction and the only one ever accessed.
+        let (a, b) = if Self::A_IS_LARGER {
+            (Some(unsafe { &self.a }.get_iter()), None)
+        } else {
+            (None, Some(unsafe { &self.b }.get_iter()))
+        };
+
+        a.into_iter().flatten().chain(b.into_iter().flatten())
+    }
+
+    fn get_iter_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut E::Word>
+    where
+        E::Word: 'a,
+    {
+        const {
+            assert!(
+                (Self::A_IS_LARGER && A::SIZE == Self::SIZE)
+                    || (!Self::A_IS_LARGER && B::SIZE == Self::SIZE),
+                "The medium with the larger footprint must also be the larger medium."
+            )
+        };
+
+        // SAFETY: the larger medium is the one initialized
+        // upon construction and the only one ever accessed.
+        let (a, b) = if Self::A_IS_LARGER {
+            (Some(unsafe { &mut self.a }.get_iter_mut()), None)
+        } else {
+            (None, Some(unsafe { &mut self.b }.get_iter_mut()))
+        };
+
+        a.into_iter().flatten().chain(b.into_iter().flatten())
+    }
+}
+
+/// A medium consisting of `N` consecutive mediums.
+pub struct Repeat<M, const N: usize>(pub [M; N]);
+
+impl<M: Default, const N: usize> Default for Repeat<M, N> {
+    fn default() -> Self {
+        Self(core::array::from_fn(|_| M::default()))
+    }
+}
+
+impl<E: Encoding, M: Medium<E>, const N: usize> Medium<E> for Repeat<M, N> {
+    const SIZE: usize = M::SIZE * N;
+
+    fn get_iter<'a>(&'a self) -> impl Iterator<Item = &'a E::Word>
+    where
+        E::Word: 'a,
+    {
+        self.0.iter().flat_map(|medium| medium.get_iter())
+    }
+
+    fn get_iter_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut E::Word>
+    where
+        E::Word: 'a,
+    {
+        self.0.iter_mut().flat_map(|medium| medium.get_iter_mut())
+    }
+}
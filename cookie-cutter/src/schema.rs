@@ -0,0 +1,735 @@
+//! Static descriptions of the serialized layout of types.
+//!
+//! Every `SerializeBuf` type provides a [`Schema`] as `SCHEMA`,
+//! which the derives fill in with fields, tags and encodings,
+//! so tools on the other end of the medium need not mirror
+//! the types by hand.
+//!
+//! Peers may compare [`Schema::fingerprint`]s, available as `FINGERPRINT`,
+//! to refuse frames of incompatible layouts.
+//!
+//! Schemas are displayed in a stable textual form, e.g.
+//!
+//! ```text
+//! struct Frame (8 words), magic u8 = 0xa5 {
+//!     id: u16,
+//!     mode: enum Mode (3 words), tag u8 {
+//!         Off = 0,
+//!         On = 1 {
+//!             level: u16,
+//!         },
+//!     },
+//!     gain: i8, scale 0.5,
+//!     samples: [u8; 2], len id,
+//! }
+//! ```
+
+use core::{fmt, ops::Bound};
+
+use crate::encoding::{vanilla::padding, Endian};
+
+/// The serialized layout of a type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schema {
+    /// The name of the type as declared, empty if unknown.
+    pub name: &'static str,
+    /// The maximum serialized size in words.
+    pub size: usize,
+    pub kind: Kind,
+}
+
+/// How a type is serialized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    /// Not described, e.g. implemented by hand.
+    Opaque,
+    /// A single word, 0 or 1.
+    Bool,
+    /// An unsigned integer of `size` words.
+    Unsigned,
+    /// A two's complement integer of `size` words.
+    Signed,
+    /// A floating point number of `size` words.
+    Float { format: FloatFormat },
+    /// A fixed-point number with `frac` fractional bits, serialized as `bits`.
+    Fixed { bits: &'static Schema, frac: u32 },
+    /// Elements serialized in order.
+    Array { len: usize, item: &'static Schema },
+    /// Elements serialized in order.
+    Tuple(&'static [&'static Schema]),
+    /// Fields serialized in order.
+    Struct {
+        container: Container,
+        fields: &'static [Field],
+    },
+    /// A tag followed by the fields of the variant it identifies.
+    Enum {
+        container: Container,
+        tag: &'static Schema,
+        variants: &'static [Variant],
+    },
+}
+
+/// The encoding of a floating point number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// IEEE 754 binary16, i.e. `half::f16`.
+    Binary16,
+    /// IEEE 754 binary32, i.e. `f32`.
+    Binary32,
+    /// IEEE 754 binary64, i.e. `f64`.
+    Binary64,
+    /// The upper half of binary32, i.e. `half::bf16`.
+    BFloat16,
+}
+
+/// The encoding of a struct or enum as a whole.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Container {
+    /// The order of multi-word values within the type, if not that of the enclosing type.
+    pub endian: Option<Endian>,
+    /// The constant preceding the type.
+    pub magic: Option<Constant>,
+    /// The current version, following the constant.
+    pub version: Option<Constant>,
+    /// The number of reserved words following the type.
+    pub pad: Option<usize>,
+    /// The multiple the size of the type is padded to.
+    pub align: Option<usize>,
+    /// Whether reserved words must be zero upon deserialization.
+    pub zeroed: bool,
+}
+
+/// A constant on the wire.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constant {
+    pub schema: &'static Schema,
+    /// The value as written in the source.
+    pub value: &'static str,
+    /// The value as parsed, which is hashed rather than its spelling.
+    pub parsed: Value,
+}
+
+/// The accepted values of a field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    /// The range as written in the source.
+    pub text: &'static str,
+    /// The bounds, or `None` if the range is not a range
+    /// expression, e.g. a constant, in which case it is hashed as written.
+    pub bounds: Option<(Bound<Value>, Bound<Value>)>,
+}
+
+/// A value given to an attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    /// An integer literal.
+    Int(i128),
+    /// A floating point literal.
+    Float(f64),
+    /// A byte string literal.
+    Bytes(&'static [u8]),
+    /// Any other expression, as written in the source.
+    Expr(&'static str),
+}
+
+/// A serialized field of a struct or enum variant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field {
+    /// The name of the field, or its index for tuples.
+    pub name: &'static str,
+    /// The layout of the field on the wire.
+    pub schema: &'static Schema,
+    /// The order of multi-word values within the field, if not that of the enclosing type.
+    pub endian: Option<Endian>,
+    /// The earlier field holding the number of elements serialized.
+    pub len: Option<&'static str>,
+    /// The value of a unit on the wire.
+    pub scale: Option<f64>,
+    /// The constant serialized instead of the value.
+    pub magic: Option<Constant>,
+    /// The accepted values.
+    pub range: Option<Range>,
+    /// The number of reserved words preceding the field.
+    pub pad: Option<usize>,
+    /// The multiple the offset of the field is padded to.
+    pub align: Option<usize>,
+    /// The version the field was added in.
+    pub since: Option<u64>,
+    /// The version the field was removed in.
+    pub until: Option<u64>,
+}
+
+/// A variant of an enum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Variant {
+    pub name: &'static str,
+    /// The tag identifying the variant, or `None` for
+    /// the fallback variant, which holds unknown tags.
+    pub tag: Option<i128>,
+    pub fields: &'static [Field],
+}
+
+impl Schema {
+    /// The schema of a type which is not described.
+    pub const fn opaque(size: usize) -> Self {
+        Self {
+            name: "",
+            size,
+            kind: Kind::Opaque,
+        }
+    }
+
+    /// Whether every value is serialized to `size` words, so
+    /// fields following it are at constant offsets.
+    ///
+    /// Opaque types are assumed to be.
+    pub const fn is_constant_size(&self) -> bool {
+        match &self.kind {
+            Kind::Opaque
+            | Kind::Bool
+            | Kind::Unsigned
+            | Kind::Signed
+            | Kind::Float { .. }
+            | Kind::Fixed { .. } => true,
+            Kind::Array { item, .. } => item.is_constant_size(),
+            Kind::Tuple(items) => {
+                let mut i = 0;
+
+                while i < items.len() {
+                    if !items[i].is_constant_size() {
+                        return false;
+                    }
+
+                    i += 1;
+                }
+
+                true
+            }
+            Kind::Struct { fields, .. } => {
+                let mut i = 0;
+
+                while i < fields.len() {
+                    if !fields[i].is_constant_size() {
+                        return false;
+                    }
+
+                    i += 1;
+                }
+
+                true
+            }
+            // every variant must fill the size of the largest
+            Kind::Enum {
+                container,
+                tag,
+                variants,
+            } => {
+                let start = container.header_size() + tag.size;
+                let mut i = 0;
+
+                while i < variants.len() {
+                    match container.size_of(start, variants[i].fields) {
+                        Some(size) if size == self.size => {}
+                        _ => return false,
+                    }
+
+                    i += 1;
+                }
+
+                true
+            }
+        }
+    }
+
+    /// A hash of the layout on the wire, i.e. field order, types,
+    /// tags and encoding, which is stable across builds.
+    ///
+    /// Names do not contribute, except those of opaque types, so
+    /// renaming fields, variants or types is compatible. Neither does
+    /// the spelling of constants and ranges, only their values.
+    pub const fn fingerprint(&self) -> u64 {
+        self.hash(Hasher::new()).0
+    }
+
+    const fn hash(&self, hasher: Hasher) -> Hasher {
+        let hasher = hasher.usize(self.size);
+
+        match &self.kind {
+            Kind::Opaque => hasher.byte(0).str(self.name),
+            Kind::Bool => hasher.byte(1),
+            Kind::Unsigned => hasher.byte(2),
+            Kind::Signed => hasher.byte(3),
+            Kind::Float { format } => hasher.byte(4).byte(*format as u8),
+            Kind::Fixed { bits, frac } => bits.hash(hasher.byte(5)).u64(*frac as u64),
+            Kind::Array { len, item } => item.hash(hasher.byte(6).usize(*len)),
+            Kind::Tuple(items) => {
+                let mut hasher = hasher.byte(7).usize(items.len());
+                let mut i = 0;
+
+                while i < items.len() {
+                    hasher = items[i].hash(hasher);
+                    i += 1;
+                }
+
+                hasher
+            }
+            Kind::Struct { container, fields } => {
+                hash_fields(container.hash(hasher.byte(8)), fields)
+            }
+            Kind::Enum {
+                container,
+                tag,
+                variants,
+            } => {
+                let mut hasher = tag.hash(container.hash(hasher.byte(9)));
+                hasher = hasher.usize(variants.len());
+                let mut i = 0;
+
+                while i < variants.len() {
+                    let variant = &variants[i];
+
+                    hasher = match variant.tag {
+                        Some(tag) => hasher.byte(1).u128(tag as u128),
+                        None => hasher.byte(0),
+                    };
+                    hasher = hash_fields(hasher, variant.fields);
+                    i += 1;
+                }
+
+                hasher
+            }
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match &self.kind {
+            Kind::Opaque => write!(f, "opaque ({} words)", self.size),
+            Kind::Bool | Kind::Unsigned | Kind::Signed | Kind::Float { .. } => {
+                f.write_str(self.name)
+            }
+            Kind::Fixed { frac, .. } => write!(f, "{}<{frac}>", self.name),
+            Kind::Array { len, item } => {
+                f.write_str("[")?;
+                item.write(f, depth)?;
+                write!(f, "; {len}]")
+            }
+            Kind::Tuple(items) => {
+                f.write_str("(")?;
+
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    item.write(f, depth)?;
+                }
+
+                if items.len() == 1 {
+                    f.write_str(",")?;
+                }
+
+                f.write_str(")")
+            }
+            Kind::Struct { container, fields } => {
+                write!(f, "struct {} ({} words)", self.name, self.size)?;
+                container.write(f, depth)?;
+                write_fields(f, fields, depth)
+            }
+            Kind::Enum {
+                container,
+                tag,
+                variants,
+            } => {
+                write!(f, "enum {} ({} words), tag ", self.name, self.size)?;
+                tag.write(f, depth)?;
+                container.write(f, depth)?;
+                f.write_str(" {\n")?;
+
+                for variant in *variants {
+                    indent(f, depth + 1)?;
+
+                    match variant.tag {
+                        Some(tag) => write!(f, "{} = {tag}", variant.name)?,
+                        None => write!(f, "{} = _", variant.name)?,
+                    }
+
+                    if !variant.fields.is_empty() {
+                        write_fields(f, variant.fields, depth + 1)?;
+                    }
+
+                    f.write_str(",\n")?;
+                }
+
+                indent(f, depth)?;
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl Container {
+    /// The size of the magic constant and the version.
+    const fn header_size(&self) -> usize {
+        let magic = match &self.magic {
+            Some(magic) => magic.schema.size,
+            None => 0,
+        };
+        let version = match &self.version {
+            Some(version) => version.schema.size,
+            None => 0,
+        };
+
+        magic + version
+    }
+
+    /// The size of the type serializing `fields` after `start` words,
+    /// or `None` if any field varies in size.
+    const fn size_of(&self, start: usize, fields: &[Field]) -> Option<usize> {
+        let mut size = start;
+        let mut i = 0;
+
+        while i < fields.len() {
+            let field = &fields[i];
+
+            if !field.is_constant_size() {
+                return None;
+            }
+
+            size = pad(size, field.pad, field.align) + field.schema.size;
+            i += 1;
+        }
+
+        Some(pad(size, self.pad, self.align))
+    }
+
+    const fn hash(&self, hasher: Hasher) -> Hasher {
+        let hasher = hash_endian(hasher, self.endian);
+        let hasher = hash_constant(hasher, &self.magic);
+        let hasher = hash_constant(hasher, &self.version);
+        let hasher = hasher.option_usize(self.pad).option_usize(self.align);
+
+        hasher.byte(self.zeroed as u8)
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        if let Some(endian) = self.endian {
+            write_endian(f, endian)?;
+        }
+
+        if let Some(magic) = &self.magic {
+            f.write_str(", magic ")?;
+            magic.schema.write(f, depth)?;
+            write!(f, " = {}", magic.value)?;
+        }
+
+        if let Some(version) = &self.version {
+            f.write_str(", version ")?;
+            version.schema.write(f, depth)?;
+            write!(f, " = {}", version.value)?;
+        }
+
+        if let Some(pad) = self.pad {
+            write!(f, ", pad {pad}")?;
+        }
+
+        if let Some(align) = self.align {
+            write!(f, ", align {align}")?;
+        }
+
+        if self.zeroed {
+            f.write_str(", reserved zero")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Field {
+    /// Whether the field is always serialized to the size of its schema.
+    pub const fn is_constant_size(&self) -> bool {
+        self.len.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.schema.is_constant_size()
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{}: ", self.name)?;
+        self.schema.write(f, depth)?;
+
+        if let Some(endian) = self.endian {
+            write_endian(f, endian)?;
+        }
+
+        if let Some(len) = self.len {
+            write!(f, ", len {len}")?;
+        }
+
+        if let Some(scale) = self.scale {
+            write!(f, ", scale {scale}")?;
+        }
+
+        if let Some(magic) = &self.magic {
+            write!(f, ", magic {}", magic.value)?;
+        }
+
+        if let Some(range) = &self.range {
+            write!(f, ", range {}", range.text)?;
+        }
+
+        if let Some(pad) = self.pad {
+            write!(f, ", pad {pad}")?;
+        }
+
+        if let Some(align) = self.align {
+            write!(f, ", align {align}")?;
+        }
+
+        if let Some(since) = self.since {
+            write!(f, ", since {since}")?;
+        }
+
+        if let Some(until) = self.until {
+            write!(f, ", until {until}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// FNV-1a (64 bit), as used for hashed enum tags.
+#[derive(Clone, Copy)]
+struct Hasher(u64);
+
+impl Hasher {
+    const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    const fn byte(self, byte: u8) -> Self {
+        Self((self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+    }
+
+    const fn bytes(self, bytes: &[u8]) -> Self {
+        let mut hasher = self;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            hasher = hasher.byte(bytes[i]);
+            i += 1;
+        }
+
+        hasher
+    }
+
+    /// Hash the length first, so adjacent strings are unambiguous.
+    const fn str(self, str: &str) -> Self {
+        self.usize(str.len()).bytes(str.as_bytes())
+    }
+
+    const fn u64(self, value: u64) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    const fn u128(self, value: u128) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    /// Sizes are hashed as `u64` to be independent of the target.
+    const fn usize(self, value: usize) -> Self {
+        self.u64(value as u64)
+    }
+
+    const fn option_u64(self, value: Option<u64>) -> Self {
+        match value {
+            Some(value) => self.byte(1).u64(value),
+            None => self.byte(0),
+        }
+    }
+
+    const fn option_usize(self, value: Option<usize>) -> Self {
+        match value {
+            Some(value) => self.byte(1).usize(value),
+            None => self.byte(0),
+        }
+    }
+}
+
+const fn hash_endian(hasher: Hasher, endian: Option<Endian>) -> Hasher {
+    match endian {
+        Some(Endian::Little) => hasher.byte(1),
+        Some(Endian::Big) => hasher.byte(2),
+        None => hasher.byte(0),
+    }
+}
+
+/// Hash integers and byte strings as the words they are serialized
+/// to (in little endian), so that equal constants hash alike however
+/// they are written.
+const fn hash_constant(hasher: Hasher, constant: &Option<Constant>) -> Hasher {
+    let Some(constant) = constant else {
+        return hasher.byte(0);
+    };
+
+    let hasher = constant.schema.hash(hasher.byte(1));
+
+    match constant.parsed {
+        Value::Int(value) => {
+            let bytes = value.to_le_bytes();
+            let size = if constant.schema.size < bytes.len() {
+                constant.schema.size
+            } else {
+                bytes.len()
+            };
+
+            hasher.byte(0).usize(size).bytes(bytes.split_at(size).0)
+        }
+        Value::Bytes(bytes) => hasher.byte(0).usize(bytes.len()).bytes(bytes),
+        value => hash_value(hasher.byte(1), &value),
+    }
+}
+
+const fn hash_value(hasher: Hasher, value: &Value) -> Hasher {
+    match value {
+        Value::Int(value) => hasher.byte(0).u128(*value as u128),
+        Value::Float(value) => hasher.byte(1).u64(value.to_bits()),
+        Value::Bytes(bytes) => hasher.byte(2).usize(bytes.len()).bytes(bytes),
+        Value::Expr(text) => hasher.byte(3).str(text),
+    }
+}
+
+const fn hash_bound(hasher: Hasher, bound: &Bound<Value>) -> Hasher {
+    match bound {
+        Bound::Included(value) => hash_value(hasher.byte(1), value),
+        Bound::Excluded(value) => hash_value(hasher.byte(2), value),
+        Bound::Unbounded => hasher.byte(0),
+    }
+}
+
+const fn hash_range(hasher: Hasher, range: &Option<Range>) -> Hasher {
+    match range {
+        Some(Range {
+            bounds: Some((start, end)),
+            ..
+        }) => hash_bound(hash_bound(hasher.byte(1), start), end),
+        Some(Range { text, .. }) => hasher.byte(2).str(text),
+        None => hasher.byte(0),
+    }
+}
+
+/// Hash the fields in order, referring to the
+/// fields holding lengths by their position.
+const fn hash_fields(hasher: Hasher, fields: &[Field]) -> Hasher {
+    let mut hasher = hasher.usize(fields.len());
+    let mut i = 0;
+
+    while i < fields.len() {
+        let field = &fields[i];
+
+        hasher = field.schema.hash(hasher);
+        hasher = hash_endian(hasher, field.endian);
+        hasher = match field.len {
+            Some(len) => hasher.byte(1).option_usize(position(fields, len)),
+            None => hasher.byte(0),
+        };
+        hasher = match field.scale {
+            Some(scale) => hasher.byte(1).u64(scale.to_bits()),
+            None => hasher.byte(0),
+        };
+        hasher = hash_range(hash_constant(hasher, &field.magic), &field.range);
+        hasher = hasher.option_usize(field.pad).option_usize(field.align);
+        hasher = hasher.option_u64(field.since).option_u64(field.until);
+        i += 1;
+    }
+
+    hasher
+}
+
+/// The position of the field named `name`.
+const fn position(fields: &[Field], name: &str) -> Option<usize> {
+    let mut i = 0;
+
+    while i < fields.len() {
+        if str_eq(fields[i].name, name) {
+            return Some(i);
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
+/// Advance `size` past `pad` reserved words,
+/// then to a multiple of `align`.
+const fn pad(size: usize, pad: Option<usize>, align: Option<usize>) -> usize {
+    let size = match pad {
+        Some(pad) => size + pad,
+        None => size,
+    };
+
+    match align {
+        Some(align) => size + padding::align(size, align),
+        None => size,
+    }
+}
+
+fn indent(f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        f.write_str("    ")?;
+    }
+
+    Ok(())
+}
+
+fn write_endian(f: &mut fmt::Formatter<'_>, endian: Endian) -> fmt::Result {
+    match endian {
+        Endian::Little => f.write_str(", endian little"),
+        Endian::Big => f.write_str(", endian big"),
+    }
+}
+
+/// Write the fields enclosed in braces, one per line.
+fn write_fields(f: &mut fmt::Formatter<'_>, fields: &[Field], depth: usize) -> fmt::Result {
+    if fields.is_empty() {
+        return f.write_str(" {}");
+    }
+
+    f.write_str(" {\n")?;
+
+    for field in fields {
+        indent(f, depth + 1)?;
+        field.write(f, depth + 1)?;
+        f.write_str(",\n")?;
+    }
+
+    indent(f, depth)?;
+    f.write_str("}")
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
@@ -0,0 +1,7 @@
+//! The spans of diagnostics for misuse of the derives.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter)]
+#[cookie(endian = "middle")]
+struct Foo {
+    a: u16,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter)]
+#[repr(u8)]
+enum Foo {
+    A,
+    #[cookie(fallback)]
+    Unknown(u8),
+    #[cookie(fallback)]
+    Other(u8),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+#[cookie(align = 4)]
+struct Foo<T: cookie_cutter::SerializeBuf> {
+    a: T,
+}
+
+fn main() {}
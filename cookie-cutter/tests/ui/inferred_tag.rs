@@ -0,0 +1,11 @@
+use cookie_cutter::encoding::vanilla;
+
+const B: isize = 2;
+
+#[derive(vanilla::SerializeIter)]
+enum Foo {
+    A,
+    B = B,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter)]
+struct Foo {
+    #[cookie(len = count)]
+    items: [u8; 4],
+    count: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter)]
+union Foo {
+    a: u8,
+    b: i8,
+}
+
+fn main() {}
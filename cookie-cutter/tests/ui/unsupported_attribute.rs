@@ -0,0 +1,9 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter)]
+struct Foo {
+    #[cookie(sale = 0.5)]
+    a: f32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+struct Pre {
+    count: u8,
+    #[cookie(len = count)]
+    items: [u8; 4],
+    tail: u8,
+}
+
+const TAIL: usize = Pre::OFFSET_TAIL;
+
+fn main() {}
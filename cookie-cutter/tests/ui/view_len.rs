@@ -0,0 +1,10 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter, vanilla::SerializeBuf, vanilla::SerializeView)]
+struct Pre {
+    count: u8,
+    #[cookie(len = count)]
+    items: [u8; 4],
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use cookie_cutter::encoding::vanilla;
+
+#[derive(vanilla::SerializeIter, vanilla::SerializeBuf)]
+#[repr(u8)]
+enum Mode {
+    Off,
+    On(u32),
+}
+
+#[derive(vanilla::SerializeIter, vanilla::SerializeBuf, vanilla::SerializeView)]
+struct Outer {
+    mode: Mode,
+    flag: bool,
+}
+
+fn main() {}
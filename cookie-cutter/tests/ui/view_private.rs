@@ -0,0 +1,17 @@
+mod inner {
+    use cookie_cutter::encoding::vanilla;
+
+    #[derive(vanilla::SerializeIter, vanilla::SerializeBuf, vanilla::SerializeView)]
+    pub struct Pre {
+        pub public: u8,
+        private: u8,
+    }
+}
+
+fn main() {
+    let buf = [0; 2];
+    let view = inner::PreView::new(&buf).unwrap();
+
+    let _ = view.public();
+    let _ = view.private();
+}
@@ -2,8 +2,21 @@
 
 pub use macros::bundle;
 
+/// Bundles implement this trait, so they
+/// can be used generically.
+pub trait Bundle {
+    /// The common trait of the bundled types.
+    type Inner<'a>: ?Sized
+    where
+        Self: 'a;
+
+    /// The held value as the common trait.
+    fn inner(&mut self) -> &mut Self::Inner<'_>;
+}
+
 #[cfg(test)]
 mod tests {
+    use crate as dispatch_bundle; // for the proc macro
     use cookie_cutter::{encoding::vanilla, SerializeBuf};
     use macros::bundle;
 
@@ -137,6 +150,38 @@ mod tests {
         assert_eq!([10, 15, 20], buf);
     }
 
+    #[test]
+    fn paths() {
+        #[bundle(self::Foo)]
+        enum MyBundle {
+            A,
+            B,
+        }
+
+        let mut bundle = MyBundle::B(B);
+
+        assert_eq!(1, bundle.inner().bar());
+    }
+
+    #[test]
+    fn crate_path() {
+        mod renamed {
+            pub use crate as bundles;
+        }
+
+        #[bundle(Foo, crate = renamed::bundles)]
+        enum MyBundle {
+            A,
+            B,
+        }
+
+        fn inner<T: renamed::bundles::Bundle>(bundle: &mut T) -> &mut T::Inner<'_> {
+            bundle.inner()
+        }
+
+        assert_eq!(1, inner(&mut MyBundle::B(B)).bar());
+    }
+
     #[test]
     fn generics() {
         trait Foo {}
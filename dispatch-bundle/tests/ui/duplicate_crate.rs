@@ -0,0 +1,14 @@
+use dispatch_bundle::bundle;
+
+trait Foo {}
+
+struct A;
+
+impl Foo for A {}
+
+#[bundle(Foo, crate = dispatch_bundle, crate = dispatch_bundle)]
+enum Bundle {
+    A,
+}
+
+fn main() {}
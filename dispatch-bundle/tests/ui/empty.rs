@@ -0,0 +1,8 @@
+use dispatch_bundle::bundle;
+
+trait Foo {}
+
+#[bundle(Foo)]
+enum Bundle {}
+
+fn main() {}
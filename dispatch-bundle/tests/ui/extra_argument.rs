@@ -0,0 +1,14 @@
+use dispatch_bundle::bundle;
+
+trait Foo {}
+
+struct A;
+
+impl Foo for A {}
+
+#[bundle(Foo, packed)]
+enum Bundle {
+    A,
+}
+
+fn main() {}
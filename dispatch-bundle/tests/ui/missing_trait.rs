@@ -0,0 +1,10 @@
+use dispatch_bundle::bundle;
+
+struct A;
+
+#[bundle()]
+enum Bundle {
+    A,
+}
+
+fn main() {}
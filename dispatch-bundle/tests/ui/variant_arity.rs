@@ -0,0 +1,15 @@
+use dispatch_bundle::bundle;
+
+trait Foo {}
+
+struct A;
+
+impl Foo for A {}
+
+#[bundle(Foo)]
+enum Bundle {
+    A,
+    B(A, A),
+}
+
+fn main() {}
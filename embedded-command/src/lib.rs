@@ -1,5 +1,8 @@
 #![no_std]
 
+// for use of the derives through `#[cookie(crate = embedded_command::cookie_cutter)]`
+pub use cookie_cutter;
+
 pub mod command_buffer;
 pub mod command_processor;
 pub mod crc;
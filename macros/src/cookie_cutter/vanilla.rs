@@ -1,384 +1,3636 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use proc_macro2::{Delimiter, Literal, TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
-    Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields, Generics, Ident, Index, Path, Type,
-    Variant,
+    ext::IdentExt, meta::ParseNestedMeta, spanned::Spanned, Attribute, Data, DataEnum, DataStruct,
+    DeriveInput, Expr, ExprGroup, ExprLit, ExprParen, ExprRange, ExprUnary, Field, Fields,
+    GenericParam, Generics, Ident, Index, Lit, LitInt, LitStr, Member, Path, RangeLimits, Type,
+    UnOp, Variant, Visibility,
 };
 
+/// Whether failures are located within the deserialized
+/// value, i.e. `deserialize_iter_detailed` is implemented.
+const DETAILED: bool = cfg!(feature = "detailed-errors");
+
+/// The error for a failure of the given `Reason`,
+/// converted to the error type of the context.
+fn failure(path: &Path, reason: &str) -> TokenStream2 {
+    if DETAILED {
+        let reason = format_ident!("{}", reason);
+
+        quote! { #path::error::Reason::#reason.into() }
+    } else {
+        quote! { #path::error::Error::Invalid }
+    }
+}
+
 #[derive(Clone)]
 struct BodyInfo {
     ident: Ident,
     generics: Generics,
     path: Path,
+    attrs: ContainerAttrs,
+}
+
+/// The order of multi-word values.
+#[derive(Clone, Copy)]
+enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let lit: LitStr = meta.value()?.parse()?;
+
+        match lit.value().as_str() {
+            "little" => Ok(Self::Little),
+            "big" => Ok(Self::Big),
+            _ => Err(meta.error("Endianness must be \"little\" or \"big\".")),
+        }
+    }
+
+    fn render(&self, path: &Path) -> TokenStream2 {
+        match self {
+            Self::Little => quote! { #path::encoding::Endian::Little },
+            Self::Big => quote! { #path::encoding::Endian::Big },
+        }
+    }
+}
+
+/// A constant emitted on serialization and checked on deserialization.
+#[derive(Clone)]
+struct Magic {
+    value: TokenStream2,
+    ty: TokenStream2,
+}
+
+impl Magic {
+    /// Parse a container level constant, which is typed by its literal.
+    ///
+    /// Unsuffixed integers take the smallest unsigned type holding them.
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let lit: Lit = meta.value()?.parse()?;
+
+        match lit {
+            Lit::Int(int) if !int.suffix().is_empty() => {
+                let ty = format_ident!("{}", int.suffix());
+
+                Ok(Self {
+                    value: quote! { #int },
+                    ty: quote! { #ty },
+                })
+            }
+            Lit::Int(int) => {
+                let ty = match int.base10_parse::<u64>()? {
+                    0..=0xff => quote! { u8 },
+                    0x100..=0xffff => quote! { u16 },
+                    0x1_0000..=0xffff_ffff => quote! { u32 },
+                    _ => quote! { u64 },
+                };
+
+                Ok(Self {
+                    value: quote! { #int },
+                    ty,
+                })
+            }
+            Lit::ByteStr(bytes) => {
+                let len = bytes.value().len();
+
+                Ok(Self {
+                    value: quote! { *#bytes },
+                    ty: quote! { [u8; #len] },
+                })
+            }
+            _ => Err(meta.error("Magic constants must be integer or byte string literals.")),
+        }
+    }
+
+    /// Serialize the constant to `dst`.
+    fn serialize(&self, path: &Path, endian: &TokenStream2) -> TokenStream2 {
+        let Self { value, ty } = self;
+
+        quote! {{
+            let magic: #ty = #value;
+
+            #path::SerializeIter::serialize_iter_endian(&magic, &mut dst, #endian)?;
+        }}
+    }
+
+    /// Deserialize the constant from `src` as an expression
+    /// of its value, failing if it does not match.
+    fn deserialize(&self, path: &Path, endian: &TokenStream2) -> TokenStream2 {
+        let Self { value, ty } = self;
+        let failure = failure(path, "BadMagic");
+
+        quote! {{
+            let magic: #ty = #value;
+
+            if <#ty as #path::SerializeIter>::deserialize_iter_endian(&mut src, #endian)? != magic {
+                return Err(#failure);
+            }
+
+            magic
+        }}
+    }
+
+    /// The serialization medium of the constant.
+    fn medium(&self, path: &Path) -> TokenStream2 {
+        let ty = &self.ty;
+
+        quote! { <#ty as #path::SerializeBuf>::Serialized }
+    }
+}
+
+/// The current version of the layout, serialized as a header.
+#[derive(Clone)]
+struct Version {
+    number: u64,
+    value: LitInt,
+    ty: TokenStream2,
+}
+
+impl Version {
+    /// Parse the version, which is typed by its suffix or `u8`.
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let value: LitInt = meta.value()?.parse()?;
+        let ty = if value.suffix().is_empty() {
+            quote! { u8 }
+        } else {
+            let ty = format_ident!("{}", value.suffix());
+
+            quote! { #ty }
+        };
+
+        Ok(Self {
+            number: value.base10_parse()?,
+            value,
+            ty,
+        })
+    }
+
+    /// Serialize the current version to `dst`.
+    fn serialize(&self, path: &Path) -> TokenStream2 {
+        let Self { value, ty, .. } = self;
+
+        quote! {{
+            let version: #ty = #value;
+
+            #path::SerializeIter::serialize_iter_endian(&version, &mut dst, endian)?;
+        }}
+    }
+
+    /// Deserialize the version from `src` to `version`,
+    /// rejecting versions newer than the current one.
+    fn deserialize(&self, path: &Path) -> TokenStream2 {
+        let Self { value, ty, .. } = self;
+        let failure = failure(path, "UnknownVersion");
+
+        quote! {
+            let version = <#ty as #path::SerializeIter>::deserialize_iter_endian(&mut src, endian)?;
+
+            if version > #value {
+                return Err(#failure);
+            }
+        }
+    }
+
+    /// The serialization medium of the version.
+    fn medium(&self, path: &Path) -> TokenStream2 {
+        let ty = &self.ty;
+
+        quote! { <#ty as #path::SerializeBuf>::Serialized }
+    }
+}
+
+/// Reserved words, zero-filled on serialization.
+#[derive(Clone, Default)]
+struct Padding {
+    /// The number of reserved words.
+    pad: Option<Expr>,
+    /// Reserve words until the offset from the start
+    /// of the type is a multiple of this, after `pad`.
+    align: Option<Expr>,
+}
+
+impl Padding {
+    /// Parse `pad` or `align`, returning whether `meta` was either.
+    fn parse(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        if meta.path.is_ident("pad") {
+            self.pad = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("align") {
+            self.align = Some(meta.value()?.parse()?);
+        } else {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pad.is_none() && self.align.is_none()
+    }
+
+    /// Zero-fill the reserved words of `dst`,
+    /// `offset` counting the words serialized so far.
+    fn serialize(&self, path: &Path) -> TokenStream2 {
+        let pad = self.pad.as_ref().map(|pad| {
+            quote! {
+                #path::encoding::vanilla::padding::serialize(&mut dst, #pad)?;
+            }
+        });
+        let align = self.align.as_ref().map(|align| {
+            quote! {
+                #path::encoding::vanilla::padding::serialize(&mut dst, #path::encoding::vanilla::padding::align(offset.get(), #align))?;
+            }
+        });
+
+        quote! { #pad #align }
+    }
+
+    /// Consume the reserved words of `src`,
+    /// `offset` counting the words deserialized so far.
+    fn deserialize(&self, path: &Path, zero: bool) -> TokenStream2 {
+        let pad = self.pad.as_ref().map(|pad| {
+            quote! {
+                #path::encoding::vanilla::padding::deserialize(&mut src, #pad, #zero)?;
+            }
+        });
+        let align = self.align.as_ref().map(|align| {
+            quote! {
+                #path::encoding::vanilla::padding::deserialize(&mut src, #path::encoding::vanilla::padding::align(offset.get(), #align), #zero)?;
+            }
+        });
+
+        quote! { #pad #align }
+    }
+
+    /// Advance the `size` of everything preceding the
+    /// reserved words past them.
+    fn size(&self, path: &Path) -> TokenStream2 {
+        let pad = self.pad.as_ref().map(|pad| {
+            quote! { let size = size + #pad; }
+        });
+        let align = self.align.as_ref().map(|align| {
+            quote! { let size = size + #path::encoding::vanilla::padding::align(size, #align); }
+        });
+
+        quote! { #pad #align }
+    }
+
+    /// The serialization medium of the reserved words.
+    fn medium(&self) -> syn::Result<Option<TokenStream2>> {
+        if let Some(align) = &self.align {
+            return Err(syn::Error::new_spanned(
+                align,
+                "#[cookie(align = ...)] is not supported by generic types.",
+            ));
+        }
+
+        Ok(self.pad.as_ref().map(|pad| quote! { [u8; #pad] }))
+    }
+}
+
+/// How reserved words are treated upon deserialization.
+#[derive(Clone, Copy, Default)]
+enum Reserved {
+    #[default]
+    Ignore,
+    Zero,
+}
+
+impl Reserved {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let lit: LitStr = meta.value()?.parse()?;
+
+        match lit.value().as_str() {
+            "ignore" => Ok(Self::Ignore),
+            "zero" => Ok(Self::Zero),
+            _ => Err(meta.error("Reserved words must be \"ignore\"d or \"zero\".")),
+        }
+    }
+}
+
+/// Options specified by `#[cookie(...)]` on a struct or enum.
+#[derive(Clone, Default)]
+struct ContainerAttrs {
+    /// The order of multi-word values within the type,
+    /// regardless of the order of the enclosing type.
+    endian: Option<Endian>,
+    /// A function `fn(&Self) -> bool` the value must
+    /// satisfy upon deserialization.
+    validate: Option<Expr>,
+    /// A constant preceding the type on the wire.
+    magic: Option<Magic>,
+    /// The current version, preceding the
+    /// type on the wire after `magic`.
+    version: Option<Version>,
+    /// Reserved words following the type.
+    padding: Padding,
+    /// How reserved words within the type are
+    /// treated upon deserialization.
+    reserved: Reserved,
+    /// Derive enum tags from a hash of the variant
+    /// names instead of the discriminants.
+    hashed_tags: bool,
+    /// The type of enum tags on the wire,
+    /// regardless of the `#[repr]` of the enum.
+    tag_type: Option<Type>,
+    /// The path to `cookie_cutter`.
+    krate: Option<Path>,
+    /// Serialize the type as its memory image, spanned by the attribute.
+    pod: Option<Path>,
+}
+
+impl ContainerAttrs {
+    fn parse<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in attrs.filter(|attr| attr.path().is_ident("cookie")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    result.krate = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("endian") {
+                    result.endian = Some(Endian::parse(&meta)?);
+                } else if meta.path.is_ident("validate") {
+                    result.validate = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("magic") {
+                    result.magic = Some(Magic::parse(&meta)?);
+                } else if meta.path.is_ident("version") {
+                    result.version = Some(Version::parse(&meta)?);
+                } else if meta.path.is_ident("tag") {
+                    let mode: Ident = meta.value()?.parse()?;
+
+                    if mode != "hash" {
+                        return Err(syn::Error::new_spanned(
+                            mode,
+                            "Tags can only be derived from a `hash`.",
+                        ));
+                    }
+
+                    result.hashed_tags = true;
+                } else if meta.path.is_ident("tag_type") {
+                    result.tag_type = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("reserved") {
+                    result.reserved = Reserved::parse(&meta)?;
+                } else if meta.path.is_ident("pod") {
+                    result.pod = Some(meta.path.clone());
+                } else if !result.padding.parse(&meta)? {
+                    return Err(meta.error("Unsupported container attribute."));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// Options specified by `#[cookie(...)]` on a field.
+#[derive(Default)]
+struct FieldAttrs {
+    /// Serialize the (floating point) field as an
+    /// integer multiple of this factor.
+    scale: Option<Expr>,
+    /// The type to represent the field with on the wire,
+    /// either scaled or narrowed.
+    repr: Option<Type>,
+    /// A module providing `serialize_iter`, `deserialize_iter`
+    /// and `SIZE` to serialize the field with.
+    with: Option<Path>,
+    /// Do not serialize the field.
+    skip: bool,
+    /// The value of a skipped field upon deserialization,
+    /// `Default::default()` if not specified.
+    default: Option<Expr>,
+    /// The order of multi-word values within the field,
+    /// regardless of the order of the enclosing type.
+    endian: Option<Endian>,
+    /// An earlier field holding the number of elements
+    /// of this field which are serialized.
+    len: Option<Member>,
+    /// A range the value must lie within upon deserialization.
+    range: Option<Expr>,
+    /// A function `fn(&T) -> bool` the value must
+    /// satisfy upon deserialization.
+    validate: Option<Expr>,
+    /// A constant the field always holds on the wire.
+    magic: Option<Expr>,
+    /// Reserved words preceding the field.
+    padding: Padding,
+    /// The version the field was introduced in.
+    since: Option<u64>,
+    /// The version the field was removed in.
+    until: Option<u64>,
+}
+
+impl FieldAttrs {
+    fn parse(field: &Field) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cookie"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("scale") {
+                    result.scale = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("repr") {
+                    result.repr = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("with") {
+                    result.with = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("default") {
+                    result.default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("endian") {
+                    result.endian = Some(Endian::parse(&meta)?);
+                } else if meta.path.is_ident("len") {
+                    result.len = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("range") {
+                    result.range = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("validate") {
+                    result.validate = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("magic") {
+                    result.magic = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("since") {
+                    result.since = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("until") {
+                    result.until = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if !result.padding.parse(&meta)? {
+                    return Err(meta.error("Unsupported field attribute."));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        let error = |tokens: &dyn ToTokens, message| Err(syn::Error::new_spanned(tokens, message));
+
+        if let (Some(scale), None) = (&result.scale, &result.repr) {
+            return error(
+                scale,
+                "#[cookie(scale = ...)] requires #[cookie(repr = ...)].",
+            );
+        }
+
+        if let (Some(with), Some(_)) = (&result.with, &result.repr) {
+            return error(
+                with,
+                "#[cookie(with = ...)] cannot be combined with #[cookie(repr = ...)].",
+            );
+        }
+
+        if let Some(len) = &result.len {
+            if result.with.is_some() || result.repr.is_some() {
+                return error(len, "#[cookie(len = ...)] cannot be combined with #[cookie(with = ...)] or #[cookie(repr = ...)].");
+            }
+        }
+
+        if let Some(magic) = &result.magic {
+            if result.skip || result.with.is_some() || result.repr.is_some() || result.len.is_some()
+            {
+                return error(magic, "#[cookie(magic = ...)] cannot be combined with #[cookie(skip)], #[cookie(with = ...)], #[cookie(repr = ...)] or #[cookie(len = ...)].");
+            }
+        }
+
+        if let Some(default) = &result.default {
+            if !result.skip && !result.is_versioned() {
+                return error(default, "#[cookie(default = ...)] requires #[cookie(skip)], #[cookie(since = ...)] or #[cookie(until = ...)].");
+            }
+        }
+
+        if result.skip && result.is_versioned() {
+            return error(field, "Skipped fields cannot be versioned.");
+        }
+
+        if result.skip && (result.range.is_some() || result.validate.is_some()) {
+            return error(field, "Skipped fields are not validated.");
+        }
+
+        if result.skip && !result.padding.is_empty() {
+            return error(field, "Skipped fields cannot be padded.");
+        }
+
+        Ok(result)
+    }
+
+    /// Whether the field is only present in some versions.
+    fn is_versioned(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+}
+
+/// A field of a struct or enum variant.
+struct FieldInfo<'a> {
+    member: Member,
+    /// The variable the field is bound to, by reference when
+    /// serializing and by value when deserializing.
+    binding: Ident,
+    ty: &'a Type,
+    vis: &'a Visibility,
+    attrs: FieldAttrs,
+    /// The binding of the field holding the number of elements.
+    len: Option<Ident>,
 }
 
-fn get_repr<'a>(mut attrs: impl Iterator<Item = &'a Attribute>) -> Type {
-    attrs
-        .find(|&attr| attr.path().is_ident("repr"))
-        .expect("Enum must have #[repr(...)] attribute.")
-        .parse_args()
-        .expect("#[repr(...) can only have one type.")
-}
+impl FieldInfo<'_> {
+    /// The name of the field, or its index for tuples.
+    fn name(&self) -> String {
+        match &self.member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+
+    /// Whether the field is always serialized to its maximum size,
+    /// fields serialized `with` a module assumed to be.
+    fn is_constant_size(&self, path: &Path) -> TokenStream2 {
+        if self.len.is_some() || self.attrs.is_versioned() {
+            return quote! { false };
+        }
+
+        if self.attrs.with.is_some() {
+            return quote! { true };
+        }
+
+        let wire_ty = self.wire_ty();
+
+        quote! { <#wire_ty as #path::SerializeBuf>::SCHEMA.is_constant_size() }
+    }
+
+    /// The type which is actually serialized.
+    fn wire_ty(&self) -> &Type {
+        self.attrs.repr.as_ref().unwrap_or(self.ty)
+    }
+
+    /// The order of multi-word values, `endian` being
+    /// the order of the enclosing type.
+    fn endian(&self, path: &Path) -> TokenStream2 {
+        match &self.attrs.endian {
+            Some(endian) => endian.render(path),
+            None => quote! { endian },
+        }
+    }
+
+    /// Whether the field is present on the wire.
+    fn is_serialized(&self) -> bool {
+        !self.attrs.skip
+    }
+
+    /// Whether the value of the field is serialized,
+    /// as opposed to being skipped or constant.
+    fn is_bound(&self) -> bool {
+        self.is_serialized() && self.attrs.magic.is_none()
+    }
+
+    /// The constant the field holds on the wire.
+    fn magic(&self) -> Option<Magic> {
+        let ty = self.ty;
+
+        self.attrs.magic.as_ref().map(|magic| Magic {
+            value: quote! { #magic },
+            ty: quote! { #ty },
+        })
+    }
+
+    /// Serialize the field from its binding.
+    fn serialize(&self, path: &Path) -> TokenStream2 {
+        if !self.is_serialized() {
+            return quote! {};
+        }
+
+        if let Some(magic) = self.magic() {
+            return magic.serialize(path, &self.endian(path));
+        }
+
+        let binding = &self.binding;
+
+        if let Some(with) = &self.attrs.with {
+            return quote! {
+                #with::serialize_iter(#binding, &mut dst)?;
+            };
+        }
+
+        let ty = self.ty;
+        let wire_ty = self.wire_ty();
+        let endian = self.endian(path);
+
+        if let Some(len) = &self.len {
+            return quote! {
+                // counts which are not numbers of elements select none
+                let len = #path::encoding::vanilla::prefixed::count(*#len).unwrap_or(0);
+
+                #path::encoding::vanilla::prefixed::Prefixed::serialize_iter_prefixed(#binding, &mut dst, len, #endian)?;
+            };
+        }
+
+        let value = match (&self.attrs.scale, &self.attrs.repr) {
+            (Some(scale), _) => quote! {
+                &<#ty as #path::encoding::vanilla::scale::Scale<#wire_ty>>::to_scaled(*#binding, #scale)
+            },
+            (None, Some(_)) => quote! {
+                &<#ty as #path::encoding::vanilla::narrow::Narrow<#wire_ty>>::narrow(*#binding)
+            },
+            (None, None) => quote! { #binding },
+        };
+
+        quote! {
+            #path::SerializeIter::serialize_iter_endian(#value, &mut dst, #endian)?;
+        }
+    }
+
+    /// The number of words serializing the field from its binding writes.
+    fn serialized_len(&self, path: &Path) -> TokenStream2 {
+        if let Some(Magic { value, ty }) = self.magic() {
+            return quote! {{
+                let magic: #ty = #value;
+
+                #path::SerializeIter::serialized_len(&magic)
+            }};
+        }
+
+        let binding = &self.binding;
+
+        if let Some(with) = &self.attrs.with {
+            return quote! { #with::serialized_len(#binding) };
+        }
+
+        let ty = self.ty;
+        let wire_ty = self.wire_ty();
+
+        if let Some(len) = &self.len {
+            return quote! {
+                #path::encoding::vanilla::prefixed::Prefixed::serialized_len_prefixed(#binding, #path::encoding::vanilla::prefixed::count(*#len).unwrap_or(0))
+            };
+        }
+
+        let value = match (&self.attrs.scale, &self.attrs.repr) {
+            (Some(scale), _) => quote! {
+                &<#ty as #path::encoding::vanilla::scale::Scale<#wire_ty>>::to_scaled(*#binding, #scale)
+            },
+            (None, Some(_)) => quote! {
+                &<#ty as #path::encoding::vanilla::narrow::Narrow<#wire_ty>>::narrow(*#binding)
+            },
+            (None, None) => quote! { #binding },
+        };
+
+        quote! { #path::SerializeIter::serialized_len(#value) }
+    }
+
+    /// The value of the field when it is not present on the wire.
+    fn default(&self) -> TokenStream2 {
+        let ty = self.ty;
+
+        match &self.attrs.default {
+            Some(default) => quote! { #default },
+            None => quote! { <#ty as ::core::default::Default>::default() },
+        }
+    }
+
+    /// Whether the field is present in the current version.
+    fn is_current(&self, version: Option<&Version>) -> bool {
+        let Some(version) = version else {
+            return true;
+        };
+
+        self.attrs.since.is_none_or(|since| since <= version.number)
+            && self.attrs.until.is_none_or(|until| version.number < until)
+    }
+
+    /// A condition on the deserialized `version`
+    /// for the field to be present.
+    fn presence(&self) -> TokenStream2 {
+        let since = self.attrs.since.map(|since| {
+            let since = proc_macro2::Literal::u64_unsuffixed(since);
+
+            quote! { version >= #since }
+        });
+        let until = self.attrs.until.map(|until| {
+            let until = proc_macro2::Literal::u64_unsuffixed(until);
+
+            quote! { version < #until }
+        });
+
+        let conditions = since.into_iter().chain(until);
+
+        quote! { #(#conditions)&&* }
+    }
+
+    /// Deserialize the field as an expression of type `T`.
+    fn deserialize(&self, path: &Path) -> TokenStream2 {
+        let ty = self.ty;
+
+        if !self.is_serialized() {
+            return self.default();
+        }
+
+        let value = self.deserialize_unchecked(path);
+
+        if self.attrs.range.is_none() && self.attrs.validate.is_none() {
+            return value;
+        }
+
+        let failure = failure(path, "Validation");
+
+        let range = self.attrs.range.as_ref().map(|range| {
+            quote! {
+                if !(#range).contains(&value) {
+                    return Err(#failure);
+                }
+            }
+        });
+        let validate = self.attrs.validate.as_ref().map(|validate| {
+            quote! {
+                if !(#validate)(&value) {
+                    return Err(#failure);
+                }
+            }
+        });
+
+        quote! {{
+            let value: #ty = #value;
+
+            #range
+            #validate
+
+            value
+        }}
+    }
+
+    /// Deserialize the field as an expression of type `T`
+    /// without validation.
+    fn deserialize_unchecked(&self, path: &Path) -> TokenStream2 {
+        let ty = self.ty;
+
+        if let Some(magic) = self.magic() {
+            return magic.deserialize(path, &self.endian(path));
+        }
+
+        if let Some(with) = &self.attrs.with {
+            return quote! { #with::deserialize_iter(&mut src)? };
+        }
+
+        let wire_ty = self.wire_ty();
+        let endian = self.endian(path);
+
+        if let Some(len) = &self.len {
+            return quote! {
+                <#ty as #path::encoding::vanilla::prefixed::Prefixed>::deserialize_iter_prefixed(&mut src, #path::encoding::vanilla::prefixed::count(#len).ok_or(#path::error::Invalid)?, #endian)?
+            };
+        }
+
+        // nested failures are located further
+        let value = if DETAILED {
+            quote! {
+                <#wire_ty as #path::SerializeIter>::deserialize_iter_detailed(&mut src, #endian)?
+            }
+        } else {
+            quote! {
+                <#wire_ty as #path::SerializeIter>::deserialize_iter_endian(&mut src, #endian)?
+            }
+        };
+
+        match (&self.attrs.scale, &self.attrs.repr) {
+            (Some(scale), _) => quote! {
+                <#ty as #path::encoding::vanilla::scale::Scale<#wire_ty>>::from_scaled(#value, #scale)
+            },
+            (None, Some(_)) => quote! {
+                <#ty as #path::encoding::vanilla::narrow::Narrow<#wire_ty>>::widen(#value)
+            },
+            (None, None) => value,
+        }
+    }
+
+    /// Deserialize the field in place of its
+    /// binding, a mutable reference.
+    fn deserialize_in_place(&self, path: &Path) -> TokenStream2 {
+        let binding = &self.binding;
+
+        // only plain fields are deserialized in place,
+        // others are deserialized by value
+        if !self.is_bound()
+            || self.attrs.scale.is_some()
+            || self.attrs.repr.is_some()
+            || self.attrs.with.is_some()
+        {
+            let deser = self.deserialize(path);
+
+            return quote! { *#binding = #deser; };
+        }
+
+        if let Some(len) = &self.len {
+            let deser = self.deserialize(path);
+
+            return quote! {{
+                let #len = *#len;
+
+                *#binding = #deser;
+            }};
+        }
+
+        let endian = self.endian(path);
+        let failure = failure(path, "Validation");
+
+        let range = self.attrs.range.as_ref().map(|range| {
+            quote! {
+                if !(#range).contains(&*#binding) {
+                    return Err(#failure);
+                }
+            }
+        });
+        let validate = self.attrs.validate.as_ref().map(|validate| {
+            quote! {
+                if !(#validate)(&*#binding) {
+                    return Err(#failure);
+                }
+            }
+        });
+
+        quote! {
+            #path::SerializeIter::deserialize_in_place_endian(#binding, &mut src, #endian)?;
+
+            #range
+            #validate
+        }
+    }
+
+    /// The serialized size of the field.
+    fn size(&self, path: &Path) -> TokenStream2 {
+        if let Some(with) = &self.attrs.with {
+            return quote! { #with::SIZE.get() };
+        }
+
+        if self.len.is_some() {
+            let ty = self.ty;
+
+            return quote! {
+                <<#ty as #path::encoding::vanilla::prefixed::PrefixedBuf>::Serialized as #path::medium::Medium>::SIZE
+            };
+        }
+
+        let wire_ty = self.wire_ty();
+
+        quote! { <<#wire_ty as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE }
+    }
+
+    /// The serialization medium of the field.
+    fn medium(&self, path: &Path) -> TokenStream2 {
+        if let Some(with) = &self.attrs.with {
+            return quote! { [u8; #with::SIZE.get()] };
+        }
+
+        if self.len.is_some() {
+            let ty = self.ty;
+
+            return quote! { <#ty as #path::encoding::vanilla::prefixed::PrefixedBuf>::Serialized };
+        }
+
+        let wire_ty = self.wire_ty();
+
+        quote! { <#wire_ty as #path::SerializeBuf>::Serialized }
+    }
+}
+
+fn fields_of<'a>(fields: &'a Fields, info: &BodyInfo) -> syn::Result<Vec<FieldInfo<'a>>> {
+    let mut result: Vec<FieldInfo> = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = FieldAttrs::parse(field)?;
+
+        if attrs.is_versioned() && info.attrs.version.is_none() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "Versioned fields require #[cookie(version = ...)] on the container.",
+            ));
+        }
+
+        // the length must be known before the field is deserialized
+        let len = attrs
+            .len
+            .as_ref()
+            .map(|len| {
+                result
+                    .iter()
+                    .find(|earlier| &earlier.member == len && earlier.is_serialized())
+                    .map(|earlier| earlier.binding.clone())
+                    .ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            len,
+                            "#[cookie(len = ...)] must refer to an earlier serialized field.",
+                        )
+                    })
+            })
+            .transpose()?;
+
+        result.push(FieldInfo {
+            member: field
+                .ident
+                .clone()
+                .map(Member::Named)
+                .unwrap_or_else(|| Member::Unnamed(Index::from(i))),
+            binding: format_ident!("v{i}"),
+            ty: &field.ty,
+            vis: &field.vis,
+            attrs,
+            len,
+        });
+    }
+
+    Ok(result)
+}
+
+/// The pattern binding all serialized fields.
+fn destructure(fields: &[FieldInfo], info: &BodyInfo) -> TokenStream2 {
+    let version = info.attrs.version.as_ref();
+
+    let members = fields.iter().map(|field| &field.member);
+    // skipped, constant and outdated fields are not bound
+    let bindings = fields.iter().map(|field| {
+        if field.is_bound() && field.is_current(version) {
+            let binding = &field.binding;
+
+            quote! { #binding }
+        } else {
+            quote! { _ }
+        }
+    });
+
+    quote! { { #(#members: #bindings),* } }
+}
+
+/// Serialize all fields from their bindings, in order.
+fn serialize_fields(fields: &[FieldInfo], info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+
+    // only fields of the current version are serialized
+    let current: Vec<_> = fields
+        .iter()
+        .filter(|field| field.is_current(info.attrs.version.as_ref()))
+        .collect();
+
+    let paddings = current
+        .iter()
+        .map(|field| field.attrs.padding.serialize(path));
+    let sers = current.iter().map(|field| field.serialize(path));
+
+    quote! {
+        #(
+            #paddings
+            #sers
+        )*
+    }
+}
+
+/// Advance the `size` of everything preceding the fields
+/// past those serialized from their bindings.
+fn serialized_len_of_fields(fields: &[FieldInfo], info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+
+    let sizes = fields
+        .iter()
+        .filter(|field| field.is_serialized() && field.is_current(info.attrs.version.as_ref()))
+        .map(|field| {
+            let padding = field.attrs.padding.size(path);
+            let size = field.serialized_len(path);
+
+            quote! {
+                #padding
+                let size = size + #size;
+            }
+        });
+
+    quote! { #(#sizes)* }
+}
+
+/// Locate failures of `deser`, an expression deserializing
+/// `field` of `variant` (if any), within the field.
+fn locate(
+    deser: TokenStream2,
+    field: &FieldInfo,
+    variant: Option<&Ident>,
+    path: &Path,
+) -> TokenStream2 {
+    if !DETAILED || !field.is_serialized() {
+        return deser;
+    }
+
+    let ty = field.ty;
+    let name = field.name();
+    let variant = variant.map(|variant| {
+        let variant = variant.unraw().to_string();
+
+        quote! { .within(#path::error::Segment::Variant(#variant), 0) }
+    });
+
+    // failures are caught before leaving the field
+    quote! {{
+        let start = offset.get();
+        let result: Result<#ty, #path::error::Detailed> = (|| {
+            let value = #deser;
+
+            Ok(value)
+        })();
+
+        result.map_err(|err| err.within(#path::error::Segment::Field(#name), start) #variant)?
+    }}
+}
+
+/// Deserialize all fields of `variant` (if any) to their bindings, in order.
+fn deserialize_fields(
+    fields: &[FieldInfo],
+    info: &BodyInfo,
+    variant: Option<&Ident>,
+) -> TokenStream2 {
+    let path = &info.path;
+    let zero = matches!(info.attrs.reserved, Reserved::Zero);
+
+    let desers = fields.iter().map(|field| {
+        let binding = &field.binding;
+        let padding = field.attrs.padding.deserialize(path, zero);
+        let deser = locate(field.deserialize(path), field, variant, path);
+
+        // fields missing from the deserialized version take their default
+        if field.attrs.is_versioned() {
+            let presence = field.presence();
+            let default = field.default();
+
+            quote! {
+                let #binding = if #presence {
+                    #padding
+
+                    #deser
+                } else {
+                    #default
+                };
+            }
+        } else {
+            quote! {
+                #padding
+                let #binding = #deser;
+            }
+        }
+    });
+
+    quote! { #(#desers)* }
+}
+
+/// Deserialize all fields in place of their bindings, in order.
+fn deserialize_fields_in_place(fields: &[FieldInfo], info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+    let zero = matches!(info.attrs.reserved, Reserved::Zero);
+
+    let desers = fields.iter().map(|field| {
+        let binding = &field.binding;
+        let padding = field.attrs.padding.deserialize(path, zero);
+        let deser = field.deserialize_in_place(path);
+
+        // fields missing from the deserialized version take their default
+        if field.attrs.is_versioned() {
+            let presence = field.presence();
+            let default = field.default();
+
+            quote! {
+                if #presence {
+                    #padding
+
+                    #deser
+                } else {
+                    *#binding = #default;
+                }
+            }
+        } else {
+            quote! {
+                #padding
+                #deser
+            }
+        }
+    });
+
+    quote! { #(#desers)* }
+}
+
+/// The fields initialized from (or bound to) their bindings.
+fn construct(fields: &[FieldInfo]) -> TokenStream2 {
+    let members = fields.iter().map(|field| &field.member);
+    let bindings = fields.iter().map(|field| &field.binding);
+
+    quote! { { #(#members: #bindings),* } }
+}
+
+/// Options specified by `#[cookie(...)]` on an enum variant.
+#[derive(Default)]
+struct VariantAttrs {
+    /// Deserialize unknown tags to this variant,
+    /// which holds the tag as its only field.
+    fallback: bool,
+}
+
+impl VariantAttrs {
+    fn parse<'a>(attrs: impl Iterator<Item = &'a Attribute>) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in attrs.filter(|attr| attr.path().is_ident("cookie")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("fallback") {
+                    result.fallback = true;
+                } else {
+                    return Err(meta.error("Unsupported variant attribute."));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// A variant of an enum.
+struct VariantInfo<'a> {
+    variant: &'a Variant,
+    fields: Vec<FieldInfo<'a>>,
+    attrs: VariantAttrs,
+}
+
+fn variants_of<'a>(e: &'a DataEnum, info: &BodyInfo) -> syn::Result<Vec<VariantInfo<'a>>> {
+    let variants = e
+        .variants
+        .iter()
+        .map(|variant| {
+            Ok(VariantInfo {
+                variant,
+                fields: fields_of(&variant.fields, info)?,
+                attrs: VariantAttrs::parse(variant.attrs.iter())?,
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut fallbacks = variants.iter().filter(|variant| variant.attrs.fallback);
+
+    if let Some(fallback) = fallbacks.next() {
+        if fallback.fields.len() != 1 {
+            return Err(syn::Error::new_spanned(
+                fallback.variant,
+                "The fallback variant must have exactly one field holding the tag.",
+            ));
+        }
+    }
+
+    if let Some(fallback) = fallbacks.next() {
+        return Err(syn::Error::new_spanned(
+            fallback.variant,
+            "Only one variant may be the fallback.",
+        ));
+    }
+
+    Ok(variants)
+}
+
+/// The type of the tag of an enum, in order of precedence:
+/// `#[cookie(tag_type = ...)]`, `#[repr(...)]` or the smallest
+/// unsigned type holding all tags.
+fn get_tag_type<'a>(
+    mut attrs: impl Iterator<Item = &'a Attribute>,
+    e: &DataEnum,
+    info: &BodyInfo,
+) -> syn::Result<Type> {
+    if let Some(tag_type) = &info.attrs.tag_type {
+        return Ok(tag_type.clone());
+    }
+
+    if let Some(repr) = attrs.find(|&attr| attr.path().is_ident("repr")) {
+        return repr.parse_args();
+    }
+
+    // hashes are wider than any reasonable number of variants
+    if info.attrs.hashed_tags {
+        return syn::parse2(quote! { u32 });
+    }
+
+    // count up by one starting at any known tag, as in `build_tags`
+    let mut max = 0;
+    let mut next = 0;
+
+    for variant in &e.variants {
+        let tag = match &variant.discriminant {
+            Some((
+                _,
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(int), ..
+                }),
+            )) => int.base10_parse::<u64>()?,
+            Some((_, discriminant)) => {
+                return Err(syn::Error::new_spanned(
+                    discriminant,
+                    "Discriminants must be integer literals to infer the tag type, specify #[cookie(tag_type = ...)] instead.",
+                ))
+            }
+            None => next,
+        };
+
+        max = max.max(tag);
+        next = tag.saturating_add(1);
+    }
+
+    match max {
+        0..=0xff => syn::parse2(quote! { u8 }),
+        0x100..=0xffff => syn::parse2(quote! { u16 }),
+        0x1_0000..=0xffff_ffff => syn::parse2(quote! { u32 }),
+        _ => syn::parse2(quote! { u64 }),
+    }
+}
+
+fn build_tags<'a>(variants: impl Iterator<Item = &'a Variant>) -> Vec<TokenStream2> {
+    let mut tags = Vec::new();
+    let mut i = 0; // count up by one starting at any known tag
+    let mut last_anchor = quote! { 0 };
+
+    for variant in variants {
+        if let Some((_, tag)) = &variant.discriminant {
+            // a tag is provided, restart counter and update as last anchor
+            let tokens = quote! { #tag };
+            tags.push(tokens.clone());
+            i = 0;
+            last_anchor = tokens;
+        } else {
+            // a tag was not explicitly provided, we need to count up from last anchor
+            let rendered_offset = Index::from(i);
+            tags.push(quote! { #last_anchor + #rendered_offset });
+        }
+        i += 1;
+    }
+
+    tags
+}
+
+/// FNV-1a (64 bit) of the given bytes.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Build tags from the hashes of the variant names truncated to the width
+/// of `repr`, which are stable regardless of the order of the variants.
+fn build_hashed_tags<'a>(
+    variants: impl Iterator<Item = &'a Variant>,
+    repr: &Type,
+) -> syn::Result<Vec<TokenStream2>> {
+    let (bits, signed) = match quote! { #repr }.to_string().as_str() {
+        "u8" => (8, false),
+        "u16" => (16, false),
+        "u32" => (32, false),
+        "u64" => (64, false),
+        "i8" => (8, true),
+        "i16" => (16, true),
+        "i32" => (32, true),
+        "i64" => (64, true),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                repr,
+                "Hashed tags require a fixed width tag type.",
+            ))
+        }
+    };
+    let mask = u64::MAX >> (64 - bits);
+
+    let mut tags: Vec<(&Ident, u64)> = Vec::new();
+
+    for variant in variants {
+        if let Some((_, discriminant)) = &variant.discriminant {
+            return Err(syn::Error::new_spanned(
+                discriminant,
+                "Hashed tags cannot be combined with explicit discriminants.",
+            ));
+        }
+
+        let tag = fnv1a(variant.ident.to_string().as_bytes()) & mask;
+
+        if let Some((other, _)) = tags.iter().find(|(_, other)| *other == tag) {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "The hashed tags of `{other}` and `{}` collide, rename either variant.",
+                    variant.ident
+                ),
+            ));
+        }
+
+        tags.push((&variant.ident, tag));
+    }
+
+    Ok(tags
+        .into_iter()
+        .map(|(_, tag)| {
+            let tag = if signed {
+                // sign extend
+                let shift = 64 - bits;
+
+                proc_macro2::Literal::i64_unsuffixed(((tag << shift) as i64) >> shift)
+            } else {
+                proc_macro2::Literal::u64_unsuffixed(tag)
+            };
+
+            quote! { #tag }
+        })
+        .collect())
+}
+
+/// Surround a body which deserializes from `src` with the header,
+/// trailing padding and validation of the container.
+///
+/// The body evaluates to `Result<Self, _>`, or to `Result<(), _>`
+/// having overwritten `self` if `in_place`.
+fn deserialize_container(
+    info: &BodyInfo,
+    body: TokenStream2,
+    aligned: bool,
+    in_place: bool,
+) -> TokenStream2 {
+    let path = &info.path;
+    let failure = failure(path, "Validation");
+    // failures are only located when deserializing by value
+    let error = if DETAILED && !in_place {
+        quote! { #path::error::Detailed }
+    } else {
+        quote! { #path::error::Error }
+    };
+
+    // reserved words follow everything
+    let body = if info.attrs.padding.is_empty() {
+        body
+    } else {
+        let padding = info
+            .attrs
+            .padding
+            .deserialize(path, matches!(info.attrs.reserved, Reserved::Zero));
+
+        if in_place {
+            quote! {
+                let mut src = src.into_iter();
+
+                let result: Result<(), #error> = {
+                    let src = &mut src;
+
+                    #body
+                };
+                result?;
+
+                #padding
+
+                Ok(())
+            }
+        } else {
+            quote! {
+                let mut src = src.into_iter();
+
+                let value: Result<Self, #error> = {
+                    let src = &mut src;
+
+                    #body
+                };
+                let value = value?;
+
+                #padding
+
+                Ok(value)
+            }
+        }
+    };
+
+    // the version precedes everything but the constant
+    let body = if let Some(version) = &info.attrs.version {
+        let version = version.deserialize(path);
+
+        quote! {
+            let mut src = src.into_iter();
+
+            #version
+
+            #body
+        }
+    } else {
+        body
+    };
+
+    // the constant precedes everything, including enum tags
+    let body = if let Some(magic) = &info.attrs.magic {
+        let magic = magic.deserialize(path, &quote! { endian });
+
+        quote! {
+            let mut src = src.into_iter();
+
+            let _ = #magic;
+
+            #body
+        }
+    } else {
+        body
+    };
+
+    // words are counted from the start of the type,
+    // which also locates failures
+    let body = if aligned || DETAILED {
+        quote! {
+            let offset = ::core::cell::Cell::new(0usize);
+            let src = src.into_iter().inspect(|_| offset.set(offset.get() + 1));
+
+            #body
+        }
+    } else {
+        body
+    };
+
+    // the container is validated once all fields are deserialized (and validated)
+    if let Some(validate) = &info.attrs.validate {
+        if in_place {
+            quote! {
+                let result: Result<(), #error> = { #body };
+                result?;
+
+                if !(#validate)(&*self) {
+                    return Err(#failure);
+                }
+
+                Ok(())
+            }
+        } else {
+            quote! {
+                let value: Result<Self, #error> = { #body };
+                let value = value?;
+
+                if !(#validate)(&value) {
+                    return Err(#failure);
+                }
+
+                Ok(value)
+            }
+        }
+    } else {
+        body
+    }
+}
+
+/// Generate the `SerializeIter` implementation given bodies
+/// which serialize to `dst` and deserialize from `src`
+/// with multi-word values in the order `endian`, either
+/// to a new value or overwriting `self`, and a body which
+/// advances the serialized `size` of the header past `self`.
+///
+/// If `aligned`, the number of words serialized or
+/// deserialized so far is tracked by `offset`.
+fn impl_serialize_iter(
+    info: &BodyInfo,
+    ser_body: TokenStream2,
+    deser_body: TokenStream2,
+    in_place_body: TokenStream2,
+    len_body: TokenStream2,
+    aligned: bool,
+) -> TokenStream2 {
+    let implementer = &info.ident;
+    let path = &info.path;
+    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
+
+    // the order of the container overrides that of the enclosing type
+    let endian = info.attrs.endian.map(|endian| {
+        let endian = endian.render(path);
+
+        quote! { let endian = #endian; }
+    });
+
+    // reserved words follow everything
+    let ser_body = if info.attrs.padding.is_empty() {
+        ser_body
+    } else {
+        let padding = info.attrs.padding.serialize(path);
+
+        quote! {
+            let mut dst = dst.into_iter();
+
+            let result: Result<(), #path::error::EndOfInput> = {
+                let dst = &mut dst;
+
+                #ser_body
+            };
+            result?;
+
+            #padding
+
+            Ok(())
+        }
+    };
+
+    // the version precedes everything but the constant
+    let ser_body = if let Some(version) = &info.attrs.version {
+        let version = version.serialize(path);
+
+        quote! {
+            let mut dst = dst.into_iter();
+
+            #version
+
+            #ser_body
+        }
+    } else {
+        ser_body
+    };
+
+    // the constant precedes everything, including enum tags
+    let ser_body = if let Some(magic) = &info.attrs.magic {
+        let magic = magic.serialize(path, &quote! { endian });
+
+        quote! {
+            let mut dst = dst.into_iter();
+
+            #magic
+
+            #ser_body
+        }
+    } else {
+        ser_body
+    };
+
+    // words are counted from the start of the type
+    let ser_body = if aligned {
+        quote! {
+            let offset = ::core::cell::Cell::new(0usize);
+            let dst = dst.into_iter().inspect(|_| offset.set(offset.get() + 1));
+
+            #ser_body
+        }
+    } else {
+        ser_body
+    };
+
+    // the header is of constant size
+    let len_body = {
+        let start = size_of_header(info);
+        let padding = info.attrs.padding.size(path);
+
+        quote! {
+            let size = #start;
+            let size = { #len_body };
+            #padding
+
+            size
+        }
+    };
+
+    let deser_body = deserialize_container(info, deser_body, aligned, false);
+    let in_place_body = deserialize_container(info, in_place_body, aligned, true);
+
+    // failures are located by `deserialize_iter_detailed`,
+    // which deserialization by value defers to
+    let (deser_body, deser_detailed, lints) = if DETAILED {
+        let name = implementer.unraw().to_string();
+
+        (
+            quote! {
+                Self::deserialize_iter_detailed(src, endian).map_err(Into::into)
+            },
+            Some(quote! {
+                fn deserialize_iter_detailed<'a>(src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>, endian: #path::encoding::Endian) -> Result<Self, #path::error::Detailed>
+                where
+                    <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+                {
+                    #endian
+
+                    // failures are caught to be located within the type
+                    let result: Result<Self, #path::error::Detailed> = (|| { #deser_body })();
+
+                    result.map_err(|err| err.of(#name))
+                }
+            }),
+            Some(quote! { #[allow(clippy::redundant_closure_call, clippy::result_large_err)] }),
+        )
+    } else {
+        (deser_body, None, None)
+    };
+
+    quote! {
+        #lints
+        impl #impl_generics #path::SerializeIter for #implementer #ty_generics #where_clause {
+            fn serialize_iter<'a>(&self, dst: impl IntoIterator<Item = &'a mut <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<(), #path::error::EndOfInput>
+            where
+                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+            {
+                self.serialize_iter_endian(dst, #path::encoding::Endian::Little)
+            }
+
+            fn deserialize_iter<'a>(src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<Self, #path::error::Error>
+            where
+                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+            {
+                Self::deserialize_iter_endian(src, #path::encoding::Endian::Little)
+            }
+
+            fn serialized_len(&self) -> usize {
+                #len_body
+            }
+
+            fn serialize_iter_endian<'a>(&self, dst: impl IntoIterator<Item = &'a mut <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>, endian: #path::encoding::Endian) -> Result<(), #path::error::EndOfInput>
+            where
+                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+            {
+                #endian
+
+                #ser_body
+            }
+
+            fn deserialize_iter_endian<'a>(src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>, endian: #path::encoding::Endian) -> Result<Self, #path::error::Error>
+            where
+                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+            {
+                #endian
+
+                #deser_body
+            }
+
+            #deser_detailed
+
+            fn deserialize_in_place<'a>(&mut self, src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<(), #path::error::Error>
+            where
+                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+            {
+                self.deserialize_in_place_endian(src, #path::encoding::Endian::Little)
+            }
+
+            fn deserialize_in_place_endian<'a>(&mut self, src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>, endian: #path::encoding::Endian) -> Result<(), #path::error::Error>
+            where
+                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
+            {
+                #endian
+
+                #in_place_body
+            }
+        }
+    }
+}
+
+fn serialize_struct(s: &DataStruct, info: &BodyInfo) -> syn::Result<TokenStream2> {
+    let fields = fields_of(&s.fields, info)?;
+    let aligned = info.attrs.padding.align.is_some()
+        || fields
+            .iter()
+            .any(|field| field.attrs.padding.align.is_some());
+
+    let (ser_body, deser_body, in_place_body, len_body) = if fields.is_empty() {
+        (
+            quote! {
+                let _ = dst;
+
+                Ok(())
+            },
+            quote! {
+                let _ = src;
+
+                Ok(Self {})
+            },
+            quote! {
+                let _ = src;
+
+                Ok(())
+            },
+            quote! { size },
+        )
+    } else {
+        let pattern = destructure(&fields, info);
+        let sers = serialize_fields(&fields, info);
+        let desers = deserialize_fields(&fields, info, None);
+        let in_place_desers = deserialize_fields_in_place(&fields, info);
+        let lens = serialized_len_of_fields(&fields, info);
+        let construction = construct(&fields);
+
+        (
+            quote! {
+                let mut dst = dst.into_iter();
+
+                let Self #pattern = self;
+
+                #sers
+
+                Ok(())
+            },
+            quote! {
+                let mut src = src.into_iter();
+
+                #desers
+
+                Ok(Self #construction)
+            },
+            quote! {
+                let mut src = src.into_iter();
+
+                let Self #construction = self;
+
+                #in_place_desers
+
+                Ok(())
+            },
+            quote! {
+                let Self #pattern = self;
+
+                #lens
+
+                size
+            },
+        )
+    };
+
+    Ok(impl_serialize_iter(
+        info,
+        ser_body,
+        deser_body,
+        in_place_body,
+        len_body,
+        aligned,
+    ))
+}
+
+/// Advance the `size` of everything preceding the fields past them.
+fn size_of_fields(fields: &[FieldInfo], info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+
+    let sizes = fields
+        .iter()
+        .filter(|field| field.is_serialized())
+        .map(|field| {
+            let padding = field.attrs.padding.size(path);
+            let size = field.size(path);
+
+            quote! {
+                #padding
+                let size = size + #size;
+            }
+        });
+
+    quote! { #(#sizes)* }
+}
+
+/// The size of everything preceding the body of the type.
+fn size_of_header(info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+
+    let sizes = info
+        .attrs
+        .magic
+        .iter()
+        .map(|magic| magic.medium(path))
+        .chain(
+            info.attrs
+                .version
+                .iter()
+                .map(|version| version.medium(path)),
+        )
+        .map(|medium| quote! { <#medium as #path::medium::Medium>::SIZE });
+
+    quote! { 0 #(+ #sizes)* }
+}
+
+fn size_of_struct(s: &DataStruct, info: &BodyInfo) -> syn::Result<TokenStream2> {
+    let start = size_of_header(info);
+    let fields = size_of_fields(&fields_of(&s.fields, info)?, info);
+    let padding = info.attrs.padding.size(&info.path);
+
+    Ok(quote! {{
+        let size = #start;
+
+        #fields
+        #padding
+
+        size
+    }})
+}
+
+fn medium_of_fields(fields: &[FieldInfo], info: &BodyInfo) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+
+    fields
+        .iter()
+        .filter(|field| field.is_serialized())
+        .rev()
+        .try_fold(quote! { [u8; 0] }, |rest, field| {
+            let medium = field.medium(path);
+            let rest = quote! { #path::medium::Concat<#medium, #rest> };
+
+            Ok(match field.attrs.padding.medium()? {
+                Some(padding) => quote! { #path::medium::Concat<#padding, #rest> },
+                None => rest,
+            })
+        })
+}
+
+/// Surround the medium of the body of the type with
+/// that of the header and trailing padding.
+fn medium_of_container(body: TokenStream2, info: &BodyInfo) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+
+    let body = match info.attrs.padding.medium()? {
+        Some(padding) => quote! { #path::medium::Concat<#body, #padding> },
+        None => body,
+    };
+
+    let body = match &info.attrs.version {
+        Some(version) => {
+            let version = version.medium(path);
+
+            quote! { #path::medium::Concat<#version, #body> }
+        }
+        None => body,
+    };
+
+    Ok(match &info.attrs.magic {
+        Some(magic) => {
+            let magic = magic.medium(path);
+
+            quote! { #path::medium::Concat<#magic, #body> }
+        }
+        None => body,
+    })
+}
+
+fn medium_of_struct(s: &DataStruct, info: &BodyInfo) -> syn::Result<TokenStream2> {
+    medium_of_container(medium_of_fields(&fields_of(&s.fields, info)?, info)?, info)
+}
+
+/// The variants of an enum other than the fallback, with
+/// their tags and the names of the constants holding them.
+fn tagged_variants<'a, 'b>(
+    e: &DataEnum,
+    all_variants: &'b [VariantInfo<'a>],
+    info: &BodyInfo,
+    repr: &Type,
+) -> syn::Result<(Vec<&'b VariantInfo<'a>>, Vec<TokenStream2>, Vec<Ident>)> {
+    // tags are built from all variants to mirror the discriminants
+    let tags = if info.attrs.hashed_tags {
+        build_hashed_tags(e.variants.iter(), repr)?
+    } else {
+        build_tags(e.variants.iter())
+    };
+    let (variants, tags): (Vec<_>, Vec<_>) = all_variants
+        .iter()
+        .zip(tags)
+        .filter(|(variant, _)| !variant.attrs.fallback)
+        .unzip();
+
+    let tag_consts = variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.variant.ident;
+            format_ident!(
+                "{}_TAG",
+                inflector::cases::screamingsnakecase::to_screaming_snake_case(&ident.to_string())
+            )
+        })
+        .collect();
+
+    Ok((variants, tags, tag_consts))
+}
+
+fn serialize_enum(e: &DataEnum, info: &BodyInfo, repr: Type) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+    let all_variants = variants_of(e, info)?;
+    let (variants, tags, tag_consts) = tagged_variants(e, &all_variants, info, &repr)?;
+    let fallback = all_variants.iter().find(|variant| variant.attrs.fallback);
+    let aligned = info.attrs.padding.align.is_some()
+        || variants.iter().any(|variant| {
+            variant
+                .fields
+                .iter()
+                .any(|field| field.attrs.padding.align.is_some())
+        });
+
+    let mut ser_arms: Vec<_> = variants
+        .iter()
+        .zip(tag_consts.iter())
+        .map(|(variant, tag_const)| {
+            let ident = &variant.variant.ident;
+            let fields = &variant.fields;
+
+            let pattern = destructure(fields, info);
+            let sers = serialize_fields(fields, info);
+
+            quote! {
+                #ident #pattern => {
+                    #path::SerializeIter::serialize_iter_endian(&#tag_const, &mut dst, endian)?;
+                    #sers
+
+                    Ok(())
+                }
+            }
+        })
+        .collect();
+
+    let mut len_arms: Vec<_> = variants
+        .iter()
+        .zip(tag_consts.iter())
+        .map(|(variant, tag_const)| {
+            let ident = &variant.variant.ident;
+            let fields = &variant.fields;
+
+            let pattern = destructure(fields, info);
+            let lens = serialized_len_of_fields(fields, info);
+
+            quote! {
+                #ident #pattern => {
+                    let size = size + #path::SerializeIter::serialized_len(&#tag_const);
+                    #lens
+
+                    size
+                }
+            }
+        })
+        .collect();
+
+    let deser_arms: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.variant.ident;
+            let fields = &variant.fields;
+
+            let desers = deserialize_fields(fields, info, Some(ident));
+            let construction = construct(fields);
+
+            quote! {{
+                #desers
+
+                Ok(Self::#ident #construction)
+            }}
+        })
+        .collect();
+
+    // the variant is only overwritten in place if it is already held
+    let in_place_arms: Vec<_> = variants
+        .iter()
+        .map(|variant| {
+            let ident = &variant.variant.ident;
+            let fields = &variant.fields;
+
+            let desers = deserialize_fields(fields, info, Some(ident));
+            let construction = construct(fields);
+
+            if fields.is_empty() {
+                return quote! {{
+                    *self = Self::#ident #construction;
+
+                    Ok(())
+                }};
+            }
+
+            let in_place_desers = deserialize_fields_in_place(fields, info);
+
+            quote! {{
+                if let Self::#ident #construction = self {
+                    #in_place_desers
+                } else {
+                    #desers
+
+                    *self = Self::#ident #construction;
+                }
+
+                Ok(())
+            }}
+        })
+        .collect();
+
+    // unknown tags are either captured by the fallback variant or invalid
+    let unknown = failure(path, "UnknownTag");
+    let (deser_fallback, in_place_fallback) = if let Some(fallback) = fallback {
+        let ident = &fallback.variant.ident;
+        let member = &fallback.fields[0].member;
+
+        // the original tag is re-emitted
+        ser_arms.push(quote! {
+            #ident { #member: tag } => {
+                #path::SerializeIter::serialize_iter_endian(tag, &mut dst, endian)
+            }
+        });
+        len_arms.push(quote! {
+            #ident { #member: tag } => size + #path::SerializeIter::serialized_len(tag)
+        });
+
+        (
+            quote! { Ok(Self::#ident { #member: tag }) },
+            quote! {{
+                *self = Self::#ident { #member: tag };
+
+                Ok(())
+            }},
+        )
+    } else {
+        (quote! { Err(#unknown) }, quote! { Err(#unknown) })
+    };
+
+    let ser_body = quote! {
+        let mut dst = dst.into_iter();
+
+        #(
+            const #tag_consts: #repr = #tags;
+        )*
+
+        match self {
+            #(
+                Self::#ser_arms,
+            )*
+        }
+    };
+
+    // only the held variant is counted
+    let len_body = quote! {
+        #(
+            const #tag_consts: #repr = #tags;
+        )*
+
+        match self {
+            #(
+                Self::#len_arms,
+            )*
+        }
+    };
+
+    let deser_body = quote! {
+        let mut src = src.into_iter();
+
+        #(
+            const #tag_consts: #repr = #tags;
+        )*
+
+        let tag = <#repr as #path::SerializeIter>::deserialize_iter_endian(&mut src, endian)?;
+
+        match tag {
+            #(
+                #tag_consts => #deser_arms,
+            )*
+            _ => #deser_fallback
+        }
+    };
+
+    let in_place_body = quote! {
+        let mut src = src.into_iter();
+
+        #(
+            const #tag_consts: #repr = #tags;
+        )*
+
+        let tag = <#repr as #path::SerializeIter>::deserialize_iter_endian(&mut src, endian)?;
+
+        match tag {
+            #(
+                #tag_consts => #in_place_arms,
+            )*
+            _ => #in_place_fallback
+        }
+    };
+
+    Ok(impl_serialize_iter(
+        info,
+        ser_body,
+        deser_body,
+        in_place_body,
+        len_body,
+        aligned,
+    ))
+}
+
+fn size_of_enum(e: &DataEnum, info: &BodyInfo, repr: Type) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+    let start = size_of_header(info);
+    // the fallback variant only holds the tag
+    let sizes: Vec<_> = variants_of(e, info)?
+        .iter()
+        .filter(|variant| !variant.fields.is_empty() && !variant.attrs.fallback)
+        .map(|variant| size_of_fields(&variant.fields, info))
+        .collect();
+    let padding = info.attrs.padding.size(path);
+
+    Ok(quote! {{
+        let start = #start + <<#repr as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE;
+        let mut max = start;
+
+        #(
+            let size = {
+                let size = start;
+
+                #sizes
+
+                size
+            };
+
+            if size > max {
+                max = size;
+            }
+        )*
+
+        let size = max;
+
+        #padding
+
+        size
+    }})
+}
+
+fn medium_of_enum(e: &DataEnum, info: &BodyInfo, repr: Type) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+    // the fallback variant only holds the tag
+    let variants = variants_of(e, info)?
+        .iter()
+        .filter(|variant| !variant.attrs.fallback)
+        .map(|variant| medium_of_fields(&variant.fields, info))
+        .collect::<syn::Result<Vec<_>>>()?
+        .into_iter()
+        .rev()
+        .fold(quote! { [u8; 0] }, |rest, variant| {
+            quote! { #path::medium::Union<#variant, #rest> }
+        });
+
+    medium_of_container(
+        quote! { #path::medium::Concat<<#repr as #path::SerializeBuf>::Serialized, #variants> },
+        info,
+    )
+}
+
+/// The `OFFSET_*` and `SIZE_*` constants of the serialized fields
+/// following `start`, and the corresponding `LAYOUT` entries.
+///
+/// Constant names are prefixed with that of the variant for enums.
+fn layout_of_fields(
+    fields: &[FieldInfo],
+    info: &BodyInfo,
+    variant: Option<&Ident>,
+    start: TokenStream2,
+    vis: &Visibility,
+) -> (Vec<TokenStream2>, Vec<TokenStream2>) {
+    let path = &info.path;
+
+    let mut consts = Vec::new();
+    let mut entries = Vec::new();
+    // the maximum-size offset following the previous field,
+    // which is constant if all previous fields are of a constant size
+    let mut preceding = start;
+    let mut constant = quote! { true };
+
+    for field in fields.iter().filter(|field| field.is_serialized()) {
+        let name = field.name();
+        let (suffix, name) = match variant {
+            Some(variant) => (
+                format!("{}_{}", variant.unraw(), name),
+                format!("{}.{}", variant.unraw(), name),
+            ),
+            None => (name.clone(), name),
+        };
+        let suffix = inflector::cases::screamingsnakecase::to_screaming_snake_case(&suffix);
+        let offset_ident = format_ident!("OFFSET_{}", suffix);
+        let size_ident = format_ident!("SIZE_{}", suffix);
+
+        let padding = field.attrs.padding.size(path);
+        let size = field.size(path);
+        let offset = quote! {{
+            let size = #preceding;
+
+            #padding
+
+            size
+        }};
+
+        consts.push(quote! {
+            #[doc = concat!("The offset of `", #name, "` within the serialized medium.")]
+            #vis const #offset_ident: usize = {
+                assert!(#constant, concat!("The offset of `", #name, "` varies with the values of earlier fields."));
+
+                #offset
+            };
+            #[doc = concat!("The serialized size of `", #name, "`.")]
+            #vis const #size_ident: usize = #size;
+        });
+        entries.push(quote! {
+            #path::encoding::vanilla::layout::FieldLayout {
+                name: #name,
+                offset: if #constant { Some(#offset) } else { None },
+                size: Self::#size_ident,
+            }
+        });
+
+        let is_constant_size = field.is_constant_size(path);
+
+        preceding = quote! { #offset + Self::#size_ident };
+        constant = quote! { #constant && #is_constant_size };
+    }
+
+    (consts, entries)
+}
+
+fn layout_of_struct(
+    s: &DataStruct,
+    info: &BodyInfo,
+    vis: &Visibility,
+) -> syn::Result<(Vec<TokenStream2>, Vec<TokenStream2>)> {
+    Ok(layout_of_fields(
+        &fields_of(&s.fields, info)?,
+        info,
+        None,
+        size_of_header(info),
+        vis,
+    ))
+}
+
+fn layout_of_enum(
+    e: &DataEnum,
+    info: &BodyInfo,
+    repr: Type,
+    vis: &Visibility,
+) -> syn::Result<(Vec<TokenStream2>, Vec<TokenStream2>)> {
+    let path = &info.path;
+    let header = size_of_header(info);
+    // payloads of all variants follow the tag
+    let start = quote! {
+        #header + <<#repr as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE
+    };
+
+    let mut consts = Vec::new();
+    let mut entries = Vec::new();
+
+    // the fallback variant only holds the tag
+    for variant in variants_of(e, info)?
+        .iter()
+        .filter(|variant| !variant.attrs.fallback)
+    {
+        let (c, e) = layout_of_fields(
+            &variant.fields,
+            info,
+            Some(&variant.variant.ident),
+            start.clone(),
+            vis,
+        );
+
+        consts.extend(c);
+        entries.extend(e);
+    }
+
+    Ok((consts, entries))
+}
+
+/// The tokens as written in the source, without spaces
+/// between punctuation and other tokens, e.g. `-8.0..=8.0`.
+fn source_text(tokens: TokenStream2) -> String {
+    let mut text = String::new();
+    // adjacent identifiers and literals remain separated
+    let mut word = false;
+
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+
+                text.push_str(open);
+                text.push_str(&source_text(group.stream()));
+                text.push_str(close);
+                word = false;
+            }
+            TokenTree::Punct(punct) => {
+                text.push(punct.as_char());
+                word = false;
+            }
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if word {
+                    text.push(' ');
+                }
+
+                text.push_str(&token.to_string());
+                word = true;
+            }
+        }
+    }
+
+    text
+}
+
+/// The value of a literal, which may be negated or dereferenced,
+/// as a `schema::Value`, or `None` for other expressions.
+fn literal_value(expr: &Expr, path: &Path) -> Option<TokenStream2> {
+    let value = match expr {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Int(int) => {
+                let value = int.base10_parse::<u128>().ok()? as i128;
+
+                quote! { Int(#value) }
+            }
+            Lit::Float(float) => {
+                let value = float.base10_parse::<f64>().ok()?;
+
+                quote! { Float(#value) }
+            }
+            Lit::ByteStr(bytes) => quote! { Bytes(#bytes) },
+            _ => return None,
+        },
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(int), ..
+            }) => {
+                let value = int.base10_parse::<u128>().ok()? as i128;
+
+                quote! { Int(-#value) }
+            }
+            Expr::Lit(ExprLit {
+                lit: Lit::Float(float),
+                ..
+            }) => {
+                let value = float.base10_parse::<f64>().ok()?;
+
+                quote! { Float(-#value) }
+            }
+            _ => return None,
+        },
+        // byte strings are dereferenced to arrays
+        Expr::Unary(ExprUnary {
+            op: UnOp::Deref(_),
+            expr,
+            ..
+        })
+        | Expr::Paren(ExprParen { expr, .. })
+        | Expr::Group(ExprGroup { expr, .. }) => return literal_value(expr, path),
+        _ => return None,
+    };
+
+    Some(quote! { #path::schema::Value::#value })
+}
+
+/// The value of the given tokens as a `schema::Value`,
+/// which are kept as written unless they are a literal.
+fn schema_value(tokens: TokenStream2, path: &Path) -> TokenStream2 {
+    syn::parse2::<Expr>(tokens.clone())
+        .ok()
+        .and_then(|expr| literal_value(&expr, path))
+        .unwrap_or_else(|| {
+            let text = source_text(tokens);
+
+            quote! { #path::schema::Value::Expr(#text) }
+        })
+}
+
+/// The `schema::Range` of a `range` attribute, of which
+/// the bounds are only known if it is a range expression.
+fn schema_range(range: &Expr, path: &Path) -> TokenStream2 {
+    let text = source_text(range.to_token_stream());
+
+    let mut expr = range;
+    while let Expr::Paren(ExprParen { expr: inner, .. }) = expr {
+        expr = inner;
+    }
+
+    let bounds = match expr {
+        Expr::Range(ExprRange {
+            start, limits, end, ..
+        }) => {
+            let bound = |value: &Option<Box<Expr>>, inclusive: bool| match value {
+                Some(value) => {
+                    let value = schema_value(value.to_token_stream(), path);
+
+                    if inclusive {
+                        quote! { ::core::ops::Bound::Included(#value) }
+                    } else {
+                        quote! { ::core::ops::Bound::Excluded(#value) }
+                    }
+                }
+                None => quote! { ::core::ops::Bound::Unbounded },
+            };
+            let start = bound(start, true);
+            let end = bound(end, matches!(limits, RangeLimits::Closed(_)));
+
+            quote! { Some((#start, #end)) }
+        }
+        _ => quote! { None },
+    };
+
+    quote! {
+        #path::schema::Range {
+            text: #text,
+            bounds: #bounds,
+        }
+    }
+}
+
+/// `Some(value)` if present, `None` otherwise.
+fn option<T: ToTokens>(value: Option<T>) -> TokenStream2 {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+/// The schema of the encoding of the container as a whole.
+fn schema_of_container(info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+    let attrs = &info.attrs;
+
+    let endian = option(attrs.endian.map(|endian| endian.render(path)));
+    let magic = option(attrs.magic.as_ref().map(|magic| {
+        let ty = &magic.ty;
+        // byte strings are dereferenced to arrays
+        let text = source_text(magic.value.clone());
+        let value = text.trim_start_matches('*');
+        let parsed = schema_value(magic.value.clone(), path);
+
+        quote! {
+            #path::schema::Constant {
+                schema: <#ty as #path::SerializeBuf>::SCHEMA,
+                value: #value,
+                parsed: #parsed,
+            }
+        }
+    }));
+    let version = option(attrs.version.as_ref().map(|version| {
+        let ty = &version.ty;
+        let value = version.number.to_string();
+        let parsed = version.number as i128;
+
+        quote! {
+            #path::schema::Constant {
+                schema: <#ty as #path::SerializeBuf>::SCHEMA,
+                value: #value,
+                parsed: #path::schema::Value::Int(#parsed),
+            }
+        }
+    }));
+    let pad = option(attrs.padding.pad.as_ref());
+    let align = option(attrs.padding.align.as_ref());
+    let zeroed = matches!(attrs.reserved, Reserved::Zero);
+
+    quote! {
+        #path::schema::Container {
+            endian: #endian,
+            magic: #magic,
+            version: #version,
+            pad: #pad,
+            align: #align,
+            zeroed: #zeroed,
+        }
+    }
+}
+
+/// The schemas of the serialized fields.
+fn schema_of_fields(fields: &[FieldInfo], info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+
+    let entries = fields
+        .iter()
+        .filter(|field| field.is_serialized())
+        .map(|field| {
+            let name = field.name();
+            let ty = field.ty;
+            let wire_ty = field.wire_ty();
+            let attrs = &field.attrs;
+
+            // codecs are told apart by the path of their module
+            let schema = if let Some(with) = &attrs.with {
+                let name = source_text(with.to_token_stream());
+
+                quote! {
+                    &#path::schema::Schema {
+                        name: #name,
+                        ..#path::schema::Schema::opaque(#with::SIZE.get())
+                    }
+                }
+            } else if attrs.len.is_some() {
+                quote! { <#ty as #path::encoding::vanilla::prefixed::PrefixedBuf>::SCHEMA }
+            } else {
+                quote! { <#wire_ty as #path::SerializeBuf>::SCHEMA }
+            };
+            let endian = option(attrs.endian.map(|endian| endian.render(path)));
+            let len = option(attrs.len.as_ref().map(|len| match len {
+                Member::Named(ident) => ident.unraw().to_string(),
+                Member::Unnamed(index) => index.index.to_string(),
+            }));
+            let scale = option(attrs.scale.as_ref().map(|scale| quote! { (#scale) as f64 }));
+            let magic = option(attrs.magic.as_ref().map(|magic| {
+                let value = source_text(magic.to_token_stream());
+                let parsed = schema_value(magic.to_token_stream(), path);
+
+                quote! {
+                    #path::schema::Constant {
+                        schema: #schema,
+                        value: #value,
+                        parsed: #parsed,
+                    }
+                }
+            }));
+            let range = option(attrs.range.as_ref().map(|range| schema_range(range, path)));
+            let pad = option(attrs.padding.pad.as_ref());
+            let align = option(attrs.padding.align.as_ref());
+            let since = option(attrs.since.map(Literal::u64_unsuffixed));
+            let until = option(attrs.until.map(Literal::u64_unsuffixed));
+
+            quote! {
+                #path::schema::Field {
+                    name: #name,
+                    schema: #schema,
+                    endian: #endian,
+                    len: #len,
+                    scale: #scale,
+                    magic: #magic,
+                    range: #range,
+                    pad: #pad,
+                    align: #align,
+                    since: #since,
+                    until: #until,
+                }
+            }
+        });
+
+    quote! { &[#(#entries),*] }
+}
+
+fn schema_of_struct(s: &DataStruct, info: &BodyInfo) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+    let container = schema_of_container(info);
+    let fields = schema_of_fields(&fields_of(&s.fields, info)?, info);
+
+    Ok(quote! {
+        #path::schema::Kind::Struct {
+            container: #container,
+            fields: #fields,
+        }
+    })
+}
+
+fn schema_of_enum(e: &DataEnum, info: &BodyInfo, repr: Type) -> syn::Result<TokenStream2> {
+    let path = &info.path;
+    let container = schema_of_container(info);
+    let all_variants = variants_of(e, info)?;
+    let (variants, tags, _) = tagged_variants(e, &all_variants, info, &repr)?;
+
+    // variants are listed as declared
+    let entries = all_variants.iter().map(|variant| {
+        let name = variant.variant.ident.unraw().to_string();
+        let tagged = variants
+            .iter()
+            .zip(tags.iter())
+            .find(|(tagged, _)| core::ptr::eq(**tagged, variant));
+
+        // the fallback variant only holds the tag
+        match tagged {
+            Some((_, tag)) => {
+                let fields = schema_of_fields(&variant.fields, info);
+
+                quote! {
+                    #path::schema::Variant {
+                        name: #name,
+                        tag: Some({
+                            const TAG: #repr = #tag;
+
+                            TAG as i128
+                        }),
+                        fields: #fields,
+                    }
+                }
+            }
+            None => quote! {
+                #path::schema::Variant {
+                    name: #name,
+                    tag: None,
+                    fields: &[],
+                }
+            },
+        }
+    });
+
+    Ok(quote! {
+        #path::schema::Kind::Enum {
+            container: #container,
+            tag: <#repr as #path::SerializeBuf>::SCHEMA,
+            variants: &[#(#entries),*],
+        }
+    })
+}
+
+impl BodyInfo {
+    fn parse(item: &DeriveInput) -> syn::Result<Self> {
+        let attrs = ContainerAttrs::parse(item.attrs.iter())?;
+
+        Ok(Self {
+            ident: item.ident.clone(),
+            generics: item.generics.clone(),
+            // `cookie_cutter` is expected in scope unless specified
+            path: attrs
+                .krate
+                .clone()
+                .unwrap_or_else(|| syn::parse_quote! { cookie_cutter }),
+            attrs,
+        })
+    }
+}
+
+/// The error for types which are neither structs nor enums.
+fn unsupported(item: &DeriveInput) -> syn::Error {
+    match &item.data {
+        Data::Union(u) => syn::Error::new_spanned(
+            u.union_token,
+            "Vanilla serializer is only implemented for structs and enums.",
+        ),
+        _ => unreachable!("Structs and enums are supported."),
+    }
+}
+
+pub fn serialize_iter(item: TokenStream) -> TokenStream {
+    expand_serialize_iter(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_serialize_iter(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let item: DeriveInput = syn::parse2(item)?;
+    let info = BodyInfo::parse(&item)?;
+
+    match &item.data {
+        Data::Struct(s) => serialize_struct(s, &info),
+        Data::Enum(e) => {
+            let repr = get_tag_type(item.attrs.iter(), e, &info)?;
+
+            serialize_enum(e, &info, repr)
+        }
+        Data::Union(_) => Err(unsupported(&item)),
+    }
+}
+
+/// Check the type can be serialized as its memory image,
+/// returning the types of its fields.
+fn pod_fields<'a>(item: &'a DeriveInput, info: &BodyInfo) -> syn::Result<Vec<&'a Type>> {
+    let Some(pod) = &info.attrs.pod else {
+        unreachable!("Only #[cookie(pod)] types are checked.");
+    };
+
+    let Data::Struct(s) = &item.data else {
+        return Err(syn::Error::new_spanned(
+            pod,
+            "#[cookie(pod)] is only supported for structs.",
+        ));
+    };
+
+    if !info.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &info.generics,
+            "#[cookie(pod)] is not supported by generic types.",
+        ));
+    }
+
+    // the order of fields in memory must be that of declaration
+    let mut repr_c = false;
+
+    for attr in item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            repr_c |= meta.path.is_ident("C") || meta.path.is_ident("transparent");
+
+            // e.g. `align(4)`
+            if meta.input.peek(syn::token::Paren) {
+                let _ = meta.input.parse::<proc_macro2::Group>()?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    if !repr_c {
+        return Err(syn::Error::new_spanned(
+            pod,
+            "#[cookie(pod)] requires #[repr(C)] or #[repr(transparent)].",
+        ));
+    }
+
+    let attrs = &info.attrs;
+
+    if attrs.endian.is_some()
+        || attrs.validate.is_some()
+        || attrs.magic.is_some()
+        || attrs.version.is_some()
+        || !attrs.padding.is_empty()
+    {
+        return Err(syn::Error::new_spanned(
+            pod,
+            "#[cookie(pod)] cannot be combined with other container attributes.",
+        ));
+    }
+
+    s.fields
+        .iter()
+        .map(|field| {
+            match field
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("cookie"))
+            {
+                Some(attr) => Err(syn::Error::new_spanned(
+                    attr,
+                    "Fields of #[cookie(pod)] types cannot have attributes.",
+                )),
+                None => Ok(&field.ty),
+            }
+        })
+        .collect()
+}
+
+pub fn serialize_buf(item: TokenStream) -> TokenStream {
+    expand_serialize_buf(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_serialize_buf(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let item: DeriveInput = syn::parse2(item)?;
+    let info = BodyInfo::parse(&item)?;
+
+    // the length of an array cannot depend on generic parameters,
+    // so generic types are serialized to a composition of the
+    // mediums of their constituents instead
+    let serialized = if info.generics.params.is_empty() {
+        let size = match &item.data {
+            Data::Struct(s) => size_of_struct(s, &info)?,
+            Data::Enum(e) => {
+                let repr = get_tag_type(item.attrs.iter(), e, &info)?;
+
+                size_of_enum(e, &info, repr)?
+            }
+            Data::Union(_) => return Err(unsupported(&item)),
+        };
+
+        quote! { [u8; #size] }
+    } else {
+        match &item.data {
+            Data::Struct(s) => medium_of_struct(s, &info)?,
+            Data::Enum(e) => {
+                let repr = get_tag_type(item.attrs.iter(), e, &info)?;
+
+                medium_of_enum(e, &info, repr)?
+            }
+            Data::Union(_) => return Err(unsupported(&item)),
+        }
+    };
+
+    let kind = match &item.data {
+        Data::Struct(s) => schema_of_struct(s, &info)?,
+        Data::Enum(e) => {
+            let repr = get_tag_type(item.attrs.iter(), e, &info)?;
+
+            schema_of_enum(e, &info, repr)?
+        }
+        Data::Union(_) => return Err(unsupported(&item)),
+    };
+
+    let vis = &item.vis;
+    let (consts, entries) = match &item.data {
+        Data::Struct(s) => layout_of_struct(s, &info, vis)?,
+        Data::Enum(e) => {
+            let repr = get_tag_type(item.attrs.iter(), e, &info)?;
+
+            layout_of_enum(e, &info, repr, vis)?
+        }
+        Data::Union(_) => return Err(unsupported(&item)),
+    };
+
+    let path = &info.path;
+    let ident = &info.ident;
+    let name = ident.unraw().to_string();
+    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
+
+    // the memory image is copied at once
+    let pod = if info.attrs.pod.is_some() {
+        let tys = pod_fields(&item, &info)?;
+
+        Some(quote! {
+            // SAFETY: the fields are laid out in order and are
+            // themselves `Pod`, and there is no padding (see below)
+            unsafe impl #path::encoding::vanilla::pod::Pod for #ident
+            where
+                #(#tys: #path::encoding::vanilla::pod::Pod,)*
+            {
+            }
+
+            const _: () = assert!(
+                ::core::mem::size_of::<#ident>() == <<#ident as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE,
+                "#[cookie(pod)] types must not contain padding."
+            );
+        })
+    } else {
+        None
+    };
+    let pod_methods = pod.as_ref().map(|_| {
+        quote! {
+            fn serialize_buf(&self, dest: &mut Self::Serialized) {
+                #path::encoding::vanilla::pod::serialize(self, dest)
+            }
+
+            fn deserialize_buf(src: &Self::Serialized) -> Result<Self, #path::error::Invalid> {
+                Ok(#path::encoding::vanilla::pod::deserialize(src))
+            }
+        }
+    });
+
+    Ok(quote! {
+        unsafe impl #impl_generics #path::SerializeBuf for #ident #ty_generics #where_clause {
+            type Serialized = #serialized;
+
+            const SCHEMA: &'static #path::schema::Schema = &#path::schema::Schema {
+                name: #name,
+                size: <Self::Serialized as #path::medium::Medium>::SIZE,
+                kind: #kind,
+            };
+
+            #pod_methods
+        }
+
+        #pod
+
+        // offsets assume every field is present at its maximum size
+        #[allow(dead_code)]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#consts)*
+
+            /// The offsets and sizes of the serialized fields.
+            #vis const LAYOUT: &'static [#path::encoding::vanilla::layout::FieldLayout] = &[#(#entries),*];
+        }
+    })
+}
+
+/// The getter and setter of a field of a view,
+/// which access the field at its constant offset
+/// and are as visible as the field itself.
+fn view_accessors(field: &FieldInfo, info: &BodyInfo) -> (TokenStream2, TokenStream2) {
+    let path = &info.path;
+    let vis = field.vis;
+    let implementer = &info.ident;
+    let ty = field.ty;
+    let binding = &field.binding;
+
+    let name = field.name();
+    let (getter, setter) = match &field.member {
+        Member::Named(ident) => (ident.clone(), format_ident!("set_{}", name)),
+        Member::Unnamed(_) => (format_ident!("get_{}", name), format_ident!("set_{}", name)),
+    };
+    let suffix = inflector::cases::screamingsnakecase::to_screaming_snake_case(&name);
+    let offset = format_ident!("OFFSET_{}", suffix);
+    let size = format_ident!("SIZE_{}", suffix);
+
+    let ser = field.serialize(path);
+    let deser = field.deserialize(path);
+    let deser_unchecked = field.deserialize_unchecked(path);
+
+    // the order of the container overrides that of `serialize_buf`
+    let endian = match info.attrs.endian {
+        Some(endian) => endian.render(path),
+        None => quote! { #path::encoding::Endian::Little },
+    };
+
+    // the container may constrain the field with respect to others
+    let commit = if info.attrs.validate.is_some() {
+        quote! {
+            let mut buf = *self.buf;
+            buf[#implementer::#offset..][..#implementer::#size].copy_from_slice(&words);
+            <#implementer as #path::SerializeBuf>::deserialize_buf(&buf)?;
+
+            *self.buf = buf;
+        }
+    } else {
+        quote! {
+            self.buf[#implementer::#offset..][..#implementer::#size].copy_from_slice(&words);
+        }
+    };
+
+    let getter_doc = format!("The value of `{name}`.");
+    let setter_doc = format!(
+        "Set `{name}`, failing with `Invalid` if the value would be rejected upon deserialization."
+    );
+
+    let getter = quote! {
+        #[doc = #getter_doc]
+        #vis fn #getter(&self) -> #ty {
+            fn decode(words: &[u8], endian: #path::encoding::Endian) -> Result<#ty, #path::error::Error> {
+                let mut src = words.iter();
+
+                let value = #deser_unchecked;
+
+                Ok(value)
+            }
+
+            // the medium is validated upon construction and by the setters
+            decode(&self.buf[#implementer::#offset..][..#implementer::#size], #endian)
+                .unwrap_or_else(|_| unreachable!("The medium of a view deserializes."))
+        }
+    };
+
+    let setter = quote! {
+        #[doc = #setter_doc]
+        #vis fn #setter(&mut self, value: #ty) -> Result<(), #path::error::Invalid> {
+            fn encode(#binding: &#ty, words: &mut [u8], endian: #path::encoding::Endian) -> Result<(), #path::error::EndOfInput> {
+                let mut dst = words.iter_mut();
+
+                #ser
+
+                Ok(())
+            }
+
+            fn decode(words: &[u8], endian: #path::encoding::Endian) -> Result<#ty, #path::error::Error> {
+                let mut src = words.iter();
+
+                let value = #deser;
+
+                Ok(value)
+            }
+
+            let mut words = [0u8; #implementer::#size];
+
+            // the field is validated as it would be deserialized,
+            // its size accommodating any value
+            let _ = encode(&value, &mut words, #endian);
+            decode(&words, #endian).map_err(|_| #path::error::Invalid)?;
+
+            #commit
+
+            Ok(())
+        }
+    };
+
+    (getter, setter)
+}
+
+pub fn serialize_view(item: TokenStream) -> TokenStream {
+    expand_serialize_view(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_serialize_view(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let item: DeriveInput = syn::parse2(item)?;
+    let info = BodyInfo::parse(&item)?;
+
+    let s = match &item.data {
+        Data::Struct(s) => s,
+        Data::Enum(e) => {
+            return Err(syn::Error::new_spanned(
+                e.enum_token,
+                "Views are only supported for structs.",
+            ))
+        }
+        Data::Union(_) => return Err(unsupported(&item)),
+    };
+
+    if !info.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &info.generics,
+            "Views are not supported by generic types.",
+        ));
+    }
 
-fn build_tags<'a>(variants: impl Iterator<Item = &'a &'a Variant>) -> Vec<TokenStream2> {
-    let mut tags = Vec::new();
-    let mut i = 0; // count up by one starting at any known tag
-    let mut last_anchor = quote! { 0 };
+    let fields = fields_of(&s.fields, &info)?;
 
-    for variant in variants {
-        if let Some((_, tag)) = &variant.discriminant {
-            // a tag is provided, restart counter and update as last anchor
-            let tokens = quote! { #tag };
-            tags.push(tokens.clone());
-            i = 0;
-            last_anchor = tokens;
-        } else {
-            // a tag was not explicitly provided, we need to count up from last anchor
-            let rendered_offset = Index::from(i);
-            tags.push(quote! { #last_anchor + #rendered_offset });
+    // fields are accessed at constant offsets
+    for field in &fields {
+        if let Some(len) = &field.attrs.len {
+            return Err(syn::Error::new_spanned(
+                len,
+                "Views require constant offsets, which #[cookie(len = ...)] does not provide.",
+            ));
         }
-        i += 1;
-    }
 
-    tags
-}
+        if field.attrs.is_versioned() {
+            return Err(syn::Error::new_spanned(
+                &field.member,
+                "Views require constant offsets, which versioned fields do not provide.",
+            ));
+        }
+    }
 
-fn serialize_struct(s: DataStruct, info: &BodyInfo) -> TokenStream2 {
-    let implementer = &info.ident;
     let path = &info.path;
-    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
+    let vis = &item.vis;
+    let implementer = &info.ident;
+    let view = format_ident!("{}View", implementer);
+    let view_mut = format_ident!("{}ViewMut", implementer);
 
-    let types: Vec<_> = s.fields.iter().map(|field| &field.ty).collect();
+    let (getters, setters): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .filter(|field| field.is_bound())
+        .map(|field| view_accessors(field, &info))
+        .unzip();
 
-    let (ser_body, deser_body) = match &s.fields {
-        Fields::Unit => (quote! { Ok(()) }, quote! { Ok(Self) }),
-        Fields::Unnamed(fields) => {
-            let attr_tags: Vec<_> = fields
-                .unnamed
-                .iter()
-                .enumerate()
-                .map(|(i, _)| Index::from(i))
-                .collect();
+    // whether nested types are of a constant size is only known upon evaluation
+    let mut constant = quote! { true };
+    let mut checks = Vec::new();
 
-            (
-                quote! {
-                    let mut dst = dst.into_iter();
+    for field in fields.iter().filter(|field| field.is_serialized()) {
+        if field.is_bound() {
+            let message = format!(
+                "Views require constant offsets, which `{}` does not have as it follows a field which varies in size.",
+                field.name()
+            );
 
-                    #(
-                        #path::SerializeIter::serialize_iter(&self.#attr_tags, &mut dst)?;
-                    )*
+            checks.push(quote_spanned! {field.ty.span()=>
+                assert!(#constant, #message);
+            });
+        }
 
-                    Ok(())
-                },
-                quote! {
-                    let mut src = src.into_iter();
+        let is_constant_size = field.is_constant_size(path);
 
-                    Ok(
-                        Self(
-                            #(
-                                <#types as #path::SerializeIter>::deserialize_iter(&mut src)?,
-                            )*
-                        )
-                    )
-                },
-            )
+        constant = quote! { #constant && #is_constant_size };
+    }
+
+    Ok(quote! {
+        const _: () = {
+            #(#checks)*
+        };
+
+        #[doc = concat!("A view of a serialized [`", stringify!(#implementer), "`], of which fields are decoded upon access.")]
+        #vis struct #view<'a> {
+            buf: &'a <#implementer as #path::SerializeBuf>::Serialized,
         }
-        Fields::Named(fields) => {
-            let attr_idents: Vec<_> = fields
-                .named
-                .iter()
-                .map(|field| field.ident.as_ref().unwrap())
-                .collect();
 
-            (
-                quote! {
-                    let mut dst = dst.into_iter();
+        impl<'a> #view<'a> {
+            /// Wrap `buf`, failing with `Invalid` if it does not deserialize.
+            #vis fn new(buf: &'a <#implementer as #path::SerializeBuf>::Serialized) -> Result<Self, #path::error::Invalid> {
+                <#implementer as #path::SerializeBuf>::deserialize_buf(buf)?;
 
-                    #(
-                        #path::SerializeIter::serialize_iter(&self.#attr_idents, &mut dst)?;
-                    )*
+                Ok(Self { buf })
+            }
 
-                    Ok(())
-                },
-                quote! {
-                    let mut src = src.into_iter();
-
-                    Ok(
-                        Self {
-                            #(
-                                #attr_idents: <#types as #path::SerializeIter>::deserialize_iter(&mut src)?,
-                            )*
-                        }
-                    )
-                },
-            )
+            #(#getters)*
         }
-    };
 
-    quote! {
-        impl #impl_generics #path::SerializeIter for #implementer #ty_generics #where_clause {
-            fn serialize_iter<'a>(&self, dst: impl IntoIterator<Item = &'a mut <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<(), #path::error::EndOfInput>
-            where
-                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
-            {
-                #ser_body
-            }
+        #[doc = concat!("A mutable view of a serialized [`", stringify!(#implementer), "`], of which fields are encoded and decoded upon access.")]
+        #vis struct #view_mut<'a> {
+            buf: &'a mut <#implementer as #path::SerializeBuf>::Serialized,
+        }
 
-            fn deserialize_iter<'a>(src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<Self, #path::error::Error>
-            where
-                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
-            {
-                #deser_body
+        impl<'a> #view_mut<'a> {
+            /// Wrap `buf`, failing with `Invalid` if it does not deserialize.
+            #vis fn new(buf: &'a mut <#implementer as #path::SerializeBuf>::Serialized) -> Result<Self, #path::error::Invalid> {
+                <#implementer as #path::SerializeBuf>::deserialize_buf(buf)?;
+
+                Ok(Self { buf })
             }
+
+            #(#getters)*
+            #(#setters)*
         }
-    }
+    })
 }
 
-fn size_of_struct(s: DataStruct, info: &BodyInfo) -> TokenStream2 {
-    let types: Vec<_> = s.fields.iter().map(|field| &field.ty).collect();
-    let path = &info.path;
+/// A part of the progress of a partial deserialization,
+/// bound to `ident` within `resume`.
+struct Slot {
+    ident: Ident,
+    ty: TokenStream2,
+}
 
-    if types.is_empty() {
-        quote! { 0 }
+/// The state holding all slots as nested pairs,
+/// and the pattern binding them.
+fn nest_slots(slots: &[Slot]) -> (TokenStream2, TokenStream2) {
+    slots
+        .iter()
+        .rev()
+        .fold((quote! { () }, quote! { () }), |(ty, pattern), slot| {
+            let Slot { ident, ty: slot_ty } = slot;
+
+            (quote! { (#slot_ty, #ty) }, quote! { (#ident, #pattern) })
+        })
+}
+
+/// Consume the reserved words of `padding` from `src`, of which
+/// the number is computed upon reaching them and kept in `slot`.
+fn decode_padding(padding: &Padding, slot: &Ident, info: &BodyInfo) -> TokenStream2 {
+    let path = &info.path;
+    let zero = matches!(info.attrs.reserved, Reserved::Zero);
+    let size = padding.size(path);
+    // alignment is relative to the start of the type
+    let start = if padding.align.is_some() {
+        quote! { offset.get() }
     } else {
-        quote! { #( <<#types as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE )+* }
+        quote! { 0 }
+    };
+
+    quote! {
+        let remaining = #slot.get_or_insert_with(|| {
+            let start = #start;
+            let size = start;
+
+            #size
+
+            size - start
+        });
+
+        ::core::task::ready!(#path::encoding::vanilla::decode::reserved(remaining, src, #zero))?;
     }
 }
 
-fn serialize_enum(e: DataEnum, info: &BodyInfo, repr: Type) -> TokenStream2 {
-    let implementer = &info.ident;
+/// Resume deserializing `fields` of the variant with slots prefixed by
+/// `prefix`, returning the slots and the statements deserializing and
+/// then binding the fields.
+fn decode_fields(
+    fields: &[FieldInfo],
+    info: &BodyInfo,
+    prefix: &str,
+) -> syn::Result<(Vec<Slot>, TokenStream2, TokenStream2)> {
     let path = &info.path;
-    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
-    let variants: Vec<_> = e.variants.iter().collect();
+    let invalid = quote! { return ::core::task::Poll::Ready(Err(#path::error::Invalid)) };
 
-    let tags: Vec<_> = build_tags(variants.iter());
-    let tag_consts: Vec<_> = variants
-        .iter()
-        .map(|variant| {
-            let ident = &variant.ident;
-            format_ident!(
-                "{}_TAG",
-                inflector::cases::screamingsnakecase::to_screaming_snake_case(&ident.to_string())
-            )
-        })
-        .collect();
+    let mut slots = Vec::new();
+    let mut steps = Vec::new();
+    let mut decoded = Vec::new();
+    let mut skipped = Vec::new();
 
-    let ser_arms: Vec<_> = variants
-        .iter()
-        .zip(tag_consts.iter())
-        .map(|(variant, tag_const)| {
-            let ident = &variant.ident;
-            match &variant.fields {
-                Fields::Unit => quote! {
-                    #ident => {
-                        #path::SerializeIter::serialize_iter(&#tag_const, &mut dst)
+    for field in fields {
+        let binding = &field.binding;
+
+        if !field.is_serialized() {
+            let default = field.default();
+
+            skipped.push(quote! { let #binding = #default; });
+
+            continue;
+        }
+
+        if let Some(with) = &field.attrs.with {
+            return Err(syn::Error::new_spanned(
+                with,
+                "#[cookie(with = ...)] fields cannot be deserialized incrementally.",
+            ));
+        }
+
+        let ty = field.ty;
+        let wire_ty = field.wire_ty();
+        let endian = field.endian(path);
+        let value = format_ident!("{}{}", prefix, binding);
+        let state = format_ident!("{}{}_state", prefix, binding);
+
+        let padding = if field.attrs.padding.is_empty() {
+            None
+        } else {
+            let slot = format_ident!("{}{}_padding", prefix, binding);
+            let padding = decode_padding(&field.attrs.padding, &slot, info);
+
+            slots.push(Slot {
+                ident: slot,
+                ty: quote! { Option<usize> },
+            });
+
+            Some(padding)
+        };
+
+        let (state_ty, deser) = if let Some(Magic { value, ty }) = field.magic() {
+            (
+                quote! { <#ty as #path::encoding::vanilla::decode::Decode>::State },
+                quote! {{
+                    let magic: #ty = #value;
+
+                    if ::core::task::ready!(<#ty as #path::encoding::vanilla::decode::Decode>::resume(#state, src, #endian))? != magic {
+                        #invalid;
                     }
+
+                    magic
+                }},
+            )
+        } else if let Some(len) = &field.len {
+            let len = format_ident!("{}{}", prefix, len);
+
+            (
+                quote! { <#ty as #path::encoding::vanilla::decode::DecodePrefixed>::State },
+                quote! {{
+                    // the count precedes the field
+                    let Some(len) = #len.as_ref().map_or(Some(0), |len| #path::encoding::vanilla::prefixed::count(*len)) else {
+                        #invalid;
+                    };
+
+                    ::core::task::ready!(<#ty as #path::encoding::vanilla::decode::DecodePrefixed>::resume_prefixed(#state, src, len, #endian))?
+                }},
+            )
+        } else {
+            let wire = quote! {
+                ::core::task::ready!(<#wire_ty as #path::encoding::vanilla::decode::Decode>::resume(#state, src, #endian))?
+            };
+
+            (
+                quote! { <#wire_ty as #path::encoding::vanilla::decode::Decode>::State },
+                match (&field.attrs.scale, &field.attrs.repr) {
+                    (Some(scale), _) => quote! {
+                        <#ty as #path::encoding::vanilla::scale::Scale<#wire_ty>>::from_scaled(#wire, #scale)
+                    },
+                    (None, Some(_)) => quote! {
+                        <#ty as #path::encoding::vanilla::narrow::Narrow<#wire_ty>>::widen(#wire)
+                    },
+                    (None, None) => wire,
                 },
-                Fields::Unnamed(fields) => {
-                    let idents: Vec<_> = fields
-                        .unnamed
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _field)| {
-                            let ident = format_ident!("v{i}");
-
-                            quote! { #ident }
-                        })
-                        .collect();
-
-                    quote! {
-                        #ident(#(#idents),*) => {
-                            #path::SerializeIter::serialize_iter(&#tag_const, &mut dst)?;
-                            #(
-                                #path::SerializeIter::serialize_iter(#idents, &mut dst)?;
-                            )*
-
-                            Ok(())
-                        }
-                    }
+            )
+        };
+
+        let range = field.attrs.range.as_ref().map(|range| {
+            quote! {
+                if !(#range).contains(&value) {
+                    #invalid;
                 }
-                Fields::Named(fields) => {
-                    let idents: Vec<_> = fields
-                        .named
-                        .iter()
-                        .map(|field| field.ident.as_ref().unwrap())
-                        .collect();
-
-                    quote! {
-                        #ident{#(#idents),*} => {
-                            #path::SerializeIter::serialize_iter(&#tag_const, &mut dst)?;
-                            #(
-                                #path::SerializeIter::serialize_iter(#idents, &mut dst)?;
-                            )*
-
-                            Ok(())
-                        }
-                    }
+            }
+        });
+        let validate = field.attrs.validate.as_ref().map(|validate| {
+            quote! {
+                if !(#validate)(&value) {
+                    #invalid;
                 }
             }
-        })
-        .collect();
+        });
 
-    let deser_arms: Vec<_> = variants
-        .iter()
-        .map(|variant| {
-            let ident = &variant.ident;
-            match &variant.fields {
-                Fields::Unit => quote! {
-                    #ident
-                },
-                Fields::Unnamed(fields) => {
-                    let types: Vec<_> = fields.unnamed.iter().map(|field| &field.ty).collect();
-                    quote! {
-                        #ident (
-                            #(
-                                <#types as #path::SerializeIter>::deserialize_iter(&mut src)?,
-                            )*
-                        )
-                    }
-                }
-                Fields::Named(fields) => {
-                    let idents: Vec<_> = fields
-                        .named
-                        .iter()
-                        .map(|field| field.ident.as_ref().unwrap())
-                        .collect();
-                    let types: Vec<_> = fields.named.iter().map(|field| &field.ty).collect();
-
-                    quote! {
-                        #ident {
-                            #(
-                                #idents: <#types as #path::SerializeIter>::deserialize_iter(&mut src)?,
-                            )*
-                        }
-                    }
+        let step = quote! {
+            #padding
+
+            let value: #ty = #deser;
+
+            #range
+            #validate
+
+            *#value = Some(value);
+        };
+
+        // fields missing from the deserialized version take their default
+        let step = if field.attrs.is_versioned() {
+            let presence = field.presence();
+            let default = field.default();
+
+            quote! {
+                if #presence {
+                    #step
+                } else {
+                    *#value = Some(#default);
                 }
             }
+        } else {
+            step
+        };
+
+        steps.push(quote! {
+            if #value.is_none() {
+                #step
+            }
+        });
+
+        slots.push(Slot {
+            ident: value.clone(),
+            ty: quote! { Option<#ty> },
+        });
+        slots.push(Slot {
+            ident: state,
+            ty: state_ty,
+        });
+        decoded.push((binding, value));
+    }
+
+    // every field is deserialized by now
+    let bindings = if decoded.is_empty() {
+        None
+    } else {
+        let (bindings, values): (Vec<_>, Vec<_>) = decoded.into_iter().unzip();
+
+        Some(quote! {
+            let (#(Some(#bindings),)*) = (#(#values.take(),)*) else {
+                unreachable!("All fields are deserialized by now.");
+            };
         })
-        .collect();
+    };
 
-    quote! {
-        impl #impl_generics #path::SerializeIter for #implementer #ty_generics #where_clause {
-            fn serialize_iter<'a>(&self, dst: impl IntoIterator<Item = &'a mut <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<(), #path::error::EndOfInput>
-            where
-                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
-            {
-                let mut dst = dst.into_iter();
+    Ok((
+        slots,
+        quote! { #(#steps)* },
+        quote! {
+            #bindings
+            #(#skipped)*
+        },
+    ))
+}
 
-                #(
-                    const #tag_consts: #repr = #tags;
-                )*
+/// Generate the `Decode` implementation given the slots of the body
+/// and the body, which resumes deserializing `value` of the type.
+fn impl_decode(
+    info: &BodyInfo,
+    mut slots: Vec<Slot>,
+    body: TokenStream2,
+    aligned: bool,
+) -> TokenStream2 {
+    let implementer = &info.ident;
+    let path = &info.path;
+    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
+    let invalid = quote! { return ::core::task::Poll::Ready(Err(#path::error::Invalid)) };
+
+    // the order of the container overrides that of the enclosing type
+    let endian = info.attrs.endian.map(|endian| {
+        let endian = endian.render(path);
 
-                match self {
-                    #(
-                        Self::#ser_arms,
-                    )*
+        quote! { let endian = #endian; }
+    });
+
+    // the constant precedes everything, including enum tags
+    let magic = info.attrs.magic.as_ref().map(|Magic { value, ty }| {
+        slots.push(Slot {
+            ident: format_ident!("magic"),
+            ty: quote! { Option<#ty> },
+        });
+        slots.push(Slot {
+            ident: format_ident!("magic_state"),
+            ty: quote! { <#ty as #path::encoding::vanilla::decode::Decode>::State },
+        });
+
+        quote! {
+            if magic.is_none() {
+                let value: #ty = ::core::task::ready!(<#ty as #path::encoding::vanilla::decode::Decode>::resume(magic_state, src, endian))?;
+
+                if value != #value {
+                    #invalid;
                 }
+
+                *magic = Some(value);
             }
+        }
+    });
 
-            fn deserialize_iter<'a>(src: impl IntoIterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>) -> Result<Self, #path::error::Error>
-            where
-                <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word: 'a,
-            {
-                let mut src = src.into_iter();
+    // the version precedes everything but the constant
+    let version = info.attrs.version.as_ref().map(|Version { value, ty, .. }| {
+        slots.push(Slot {
+            ident: format_ident!("version"),
+            ty: quote! { Option<#ty> },
+        });
+        slots.push(Slot {
+            ident: format_ident!("version_state"),
+            ty: quote! { <#ty as #path::encoding::vanilla::decode::Decode>::State },
+        });
 
-                #(
-                    const #tag_consts: #repr = #tags;
-                )*
+        quote! {
+            let version = match *version {
+                Some(version) => version,
+                None => {
+                    let value: #ty = ::core::task::ready!(<#ty as #path::encoding::vanilla::decode::Decode>::resume(version_state, src, endian))?;
 
-                let tag = <#repr as #path::SerializeIter>::deserialize_iter(&mut src)?;
+                    if value > #value {
+                        #invalid;
+                    }
+
+                    *version = Some(value);
 
-                match tag {
-                    #(
-                        #tag_consts => Ok(Self::#deser_arms),
-                    )*
-                    _ => Err(#path::error::Error::Invalid)
+                    value
                 }
-            }
+            };
         }
-    }
-}
+    });
 
-fn size_of_enum(e: DataEnum, info: &BodyInfo, repr: Type) -> TokenStream2 {
-    let path = &info.path;
-    let sizes: Vec<_> = e
-        .variants
-        .iter()
-        .filter_map(|variant| {
-            if !variant.fields.is_empty() {
-                let types: Vec<_> = variant
-                    .fields
-                    .iter()
-                    .map(|field| &field.ty)
-                    .collect();
+    // words are counted from the start of the type
+    let offset = aligned.then(|| {
+        slots.push(Slot {
+            ident: format_ident!("offset"),
+            ty: quote! { ::core::cell::Cell<usize> },
+        });
 
-                Some(quote! { #(<<#types as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE)+* })
-            } else {
-                None
+        quote! {
+            let mut src = src.inspect(|_| offset.set(offset.get() + 1));
+            let src = &mut src;
+        }
+    });
+
+    // the container is validated once all fields are deserialized (and validated)
+    let validate = info.attrs.validate.as_ref().map(|validate| {
+        quote! {
+            if !(#validate)(&value) {
+                #invalid;
             }
-        })
-        .collect();
+        }
+    });
 
-    quote! {{
-        let mut max = 0;
+    let (state, pattern) = nest_slots(&slots);
 
-        #(
-            if #sizes > max {
-                max = #sizes;
+    quote! {
+        impl #impl_generics #path::encoding::vanilla::decode::Decode for #implementer #ty_generics #where_clause {
+            type State = #state;
+
+            fn resume<'a>(state: &mut Self::State, src: &mut impl Iterator<Item = &'a <#path::encoding::vanilla::Vanilla as #path::encoding::Encoding>::Word>, endian: #path::encoding::Endian) -> ::core::task::Poll<Result<Self, #path::error::Invalid>> {
+                #endian
+
+                let #pattern = state;
+
+                #offset
+                #magic
+                #version
+
+                let value = { #body };
+
+                #validate
+
+                ::core::task::Poll::Ready(Ok(value))
             }
-        )*
+        }
+    }
+}
 
-        max + <<#repr as #path::SerializeBuf>::Serialized as #path::medium::Medium>::SIZE
-    }}
+/// Consume the reserved words following everything, if any.
+fn decode_trailing(info: &BodyInfo, slots: &mut Vec<Slot>) -> Option<TokenStream2> {
+    if info.attrs.padding.is_empty() {
+        return None;
+    }
+
+    let slot = format_ident!("padding");
+    let padding = decode_padding(&info.attrs.padding, &slot, info);
+
+    slots.push(Slot {
+        ident: slot,
+        ty: quote! { Option<usize> },
+    });
+
+    Some(padding)
 }
 
-pub fn serialize_iter(item: TokenStream) -> TokenStream {
-    let item: DeriveInput = syn::parse2(item.into()).unwrap();
+fn decode_struct(s: &DataStruct, info: &BodyInfo) -> syn::Result<TokenStream2> {
+    let fields = fields_of(&s.fields, info)?;
+    let aligned = info.attrs.padding.align.is_some()
+        || fields
+            .iter()
+            .any(|field| field.attrs.padding.align.is_some());
 
-    let info = BodyInfo {
-        ident: item.ident,
-        generics: item.generics,
-        path: syn::parse2(quote! { cookie_cutter }).unwrap(),
-    };
+    let (mut slots, steps, bindings) = decode_fields(&fields, info, "")?;
+    let trailing = decode_trailing(info, &mut slots);
+    let construction = construct(&fields);
 
-    let implementation = match item.data {
-        Data::Struct(s) => serialize_struct(s, &info),
-        Data::Enum(e) => serialize_enum(e, &info, get_repr(item.attrs.iter())),
-        _ => panic!("Vanilla serializer is only implemented for structs and enums."),
+    // nothing may be read
+    let unused = fields
+        .iter()
+        .all(|field| !field.is_serialized())
+        .then(|| quote! { let _ = (&src, endian); });
+
+    let body = quote! {
+        #unused
+        #steps
+        #trailing
+        #bindings
+
+        Self #construction
     };
 
-    implementation.into()
+    Ok(impl_decode(info, slots, body, aligned))
 }
 
-pub fn serialize_buf(item: TokenStream) -> TokenStream {
-    let item: DeriveInput = syn::parse2(item.into()).unwrap();
+fn decode_enum(e: &DataEnum, info: &BodyInfo, repr: Type) -> syn::Result<TokenStream2> {
+    let implementer = &info.ident;
+    let path = &info.path;
+    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
+    let all_variants = variants_of(e, info)?;
+    let (variants, tags, tag_consts) = tagged_variants(e, &all_variants, info, &repr)?;
+    let fallback = all_variants.iter().find(|variant| variant.attrs.fallback);
+    let aligned = info.attrs.padding.align.is_some()
+        || variants.iter().any(|variant| {
+            variant
+                .fields
+                .iter()
+                .any(|field| field.attrs.padding.align.is_some())
+        });
+
+    // only the slots of the deserialized variant are held, once the tag is known
+    let state = format_ident!("{}DecodeState", implementer);
+    let slots = vec![
+        Slot {
+            ident: format_ident!("tag_state"),
+            ty: quote! { <#repr as #path::encoding::vanilla::decode::Decode>::State },
+        },
+        Slot {
+            ident: format_ident!("variant"),
+            ty: quote! { Option<#state #ty_generics> },
+        },
+    ];
+
+    let mut cases = Vec::new();
+    let mut arms = Vec::new();
+
+    for (i, variant) in variants.iter().enumerate() {
+        let ident = &variant.variant.ident;
+        let fields = &variant.fields;
+
+        let (mut variant_slots, steps, bindings) = decode_fields(fields, info, &format!("a{i}_"))?;
+        let trailing = decode_trailing(info, &mut variant_slots);
+        let construction = construct(fields);
+        let (ty, pattern) = nest_slots(&variant_slots);
 
-    if !item.generics.params.is_empty() {
-        panic!("SerializeBuf is incompatible with generic types. You may still use SerializeIter.");
+        cases.push(quote! { #ident(#ty) });
+        arms.push(quote! {
+            Some(#state::#ident(#pattern)) => {
+                #steps
+                #trailing
+                #bindings
+
+                Self::#ident #construction
+            }
+        });
     }
 
-    let info = BodyInfo {
-        ident: item.ident,
-        generics: item.generics,
-        path: syn::parse2(quote! { cookie_cutter }).unwrap(),
+    // unknown tags are either captured by the fallback variant or invalid
+    let unknown = match fallback {
+        Some(fallback) => {
+            let ident = &fallback.variant.ident;
+            let member = &fallback.fields[0].member;
+
+            let mut fallback_slots = vec![Slot {
+                ident: format_ident!("tag"),
+                ty: quote! { #repr },
+            }];
+            let trailing = decode_trailing(info, &mut fallback_slots);
+            let (ty, pattern) = nest_slots(&fallback_slots);
+
+            cases.push(quote! { #ident(#ty) });
+            arms.push(quote! {
+                Some(#state::#ident(#pattern)) => {
+                    let tag = *tag;
+
+                    #trailing
+
+                    Self::#ident { #member: tag }
+                }
+            });
+
+            quote! { #state::#ident((tag, Default::default())) }
+        }
+        None => quote! {
+            return ::core::task::Poll::Ready(Err(#path::error::Invalid))
+        },
     };
 
-    let size = match item.data {
-        Data::Struct(s) => size_of_struct(s, &info),
-        Data::Enum(e) => size_of_enum(e, &info, get_repr(item.attrs.iter())),
-        _ => panic!("Vanilla serializer is only implemented for structs and enums."),
+    // the parameters of the type are otherwise unused if no variant holds them
+    if info
+        .generics
+        .params
+        .iter()
+        .any(|param| !matches!(param, GenericParam::Const(_)))
+    {
+        cases.push(
+            quote! { __Marker(::core::marker::PhantomData<fn() -> #implementer #ty_generics>) },
+        );
+    }
+
+    let idents = variants.iter().map(|variant| &variant.variant.ident);
+
+    let body = quote! {
+        #(
+            const #tag_consts: #repr = #tags;
+        )*
+
+        if variant.is_none() {
+            let tag = ::core::task::ready!(<#repr as #path::encoding::vanilla::decode::Decode>::resume(tag_state, src, endian))?;
+
+            *variant = Some(match tag {
+                #(
+                    #tag_consts => #state::#idents(Default::default()),
+                )*
+                _ => #unknown,
+            });
+        }
+
+        match variant {
+            #(#arms)*
+            _ => unreachable!("The tag is deserialized by now."),
+        }
     };
 
-    let path = info.path;
-    let ident = info.ident;
-    let (impl_generics, ty_generics, where_clause) = info.generics.split_for_impl();
-    let ty = quote! { #ident #ty_generics };
+    let decode = impl_decode(info, slots, body, aligned);
 
-    quote! {
-        unsafe impl #impl_generics #path::SerializeBuf for #ty #ty_generics #where_clause {
-            type Serialized = [u8; #size];
+    Ok(quote! {
+        const _: () = {
+            #[doc(hidden)]
+            pub enum #state #impl_generics #where_clause {
+                #(#cases,)*
+            }
+
+            #decode
+        };
+    })
+}
+
+pub fn decode(item: TokenStream) -> TokenStream {
+    expand_decode(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_decode(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let item: DeriveInput = syn::parse2(item)?;
+    let info = BodyInfo::parse(&item)?;
+
+    match &item.data {
+        Data::Struct(s) => decode_struct(s, &info),
+        Data::Enum(e) => {
+            let repr = get_tag_type(item.attrs.iter(), e, &info)?;
+
+            decode_enum(e, &info, repr)
         }
+        Data::Union(_) => Err(unsupported(&item)),
     }
-    .into()
 }
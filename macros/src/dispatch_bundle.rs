@@ -2,23 +2,85 @@ use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
 use syn::{
-    punctuated::Punctuated, token::Paren, Field, FieldMutability, Fields, FieldsUnnamed, Ident,
-    ItemEnum, Type, Visibility,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token::Paren,
+    Field, FieldMutability, Fields, FieldsUnnamed, Ident, ItemEnum, Path, Token, Type, Visibility,
 };
 
+/// The arguments of `#[bundle(Trait, crate = path)]`.
+struct BundleArgs {
+    /// The common trait of the bundled types.
+    tr: Path,
+    /// The path to `dispatch_bundle`.
+    krate: Option<Path>,
+}
+
+impl Parse for BundleArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "A common trait must be specified: \"#[bundle(Trait)]\"",
+            ));
+        }
+
+        let tr = input.parse()?;
+        let mut krate = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+
+            // a trailing comma is accepted
+            if input.is_empty() {
+                break;
+            }
+
+            if !input.peek(Token![crate]) {
+                return Err(input.error(
+                    "Only the common trait and the crate are accepted: \"#[bundle(Trait, crate = path)]\"",
+                ));
+            }
+
+            let key: Token![crate] = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            if krate.is_some() {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "The crate is already specified.",
+                ));
+            }
+
+            krate = Some(input.parse()?);
+        }
+
+        Ok(Self { tr, krate })
+    }
+}
+
 pub fn bundle(attr: TokenStream, item: TokenStream) -> TokenStream {
-    // capture trait ident and enum body
-    let tr: Ident = syn::parse2(TokenStream2::from(attr))
-        .expect("A common trait must be specified: \"#[bundle(Trait)]\"");
-    let item = TokenStream2::from(item);
+    expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    // capture trait path and enum body
+    let BundleArgs { tr, krate } = syn::parse2(attr)?;
+    // `dispatch_bundle` is expected in scope unless specified
+    let krate = krate.unwrap_or_else(|| syn::parse_quote! { dispatch_bundle });
 
     // parse enum body
-    let mut e: ItemEnum = syn::parse2(item).expect("Bundle must be an enum.");
+    let mut e: ItemEnum =
+        syn::parse2(item).map_err(|err| syn::Error::new(err.span(), "Bundle must be an enum."))?;
 
-    assert!(
-        !e.variants.is_empty(),
-        "Bundle must contain at least one variant."
-    );
+    if e.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &e.ident,
+            "Bundle must contain at least one variant.",
+        ));
+    }
 
     // transform into proper tuple variants
     e.variants = e
@@ -46,13 +108,26 @@ pub fn bundle(attr: TokenStream, item: TokenStream) -> TokenStream {
                         unnamed: punc,
                     });
 
-                    v
+                    Ok(v)
                 }
-                Fields::Unnamed(_) => v,
-                Fields::Named(_) => panic!("Bundles cannot contain struct variants."),
+                Fields::Unnamed(_) => Ok(v),
+                Fields::Named(fields) => Err(syn::Error::new_spanned(
+                    fields,
+                    "Bundles cannot contain struct variants.",
+                )),
             }
         })
-        .collect();
+        .collect::<syn::Result<_>>()?;
+
+    // validate variants hold exactly one type
+    for variant in &e.variants {
+        if variant.fields.len() != 1 {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                "Bundle variants must hold exactly one type.",
+            ));
+        }
+    }
 
     // extract visibility, ident, variant idents/types, and generics for generation
     let vis = e.vis.clone();
@@ -82,14 +157,7 @@ pub fn bundle(attr: TokenStream, item: TokenStream) -> TokenStream {
         })
         .collect();
 
-    // validate variants hold eactly one type
-    for variant in &e.variants {
-        if variant.fields.len() != 1 {
-            panic!("Bundle variants must hold exacly one type.")
-        }
-    }
-
-    quote! {
+    Ok(quote! {
         #e
 
         // From's for each variant of the bundle
@@ -112,6 +180,14 @@ pub fn bundle(attr: TokenStream, item: TokenStream) -> TokenStream {
                 }
             }
         }
-    }
-    .into()
+
+        impl #impl_generics #krate::Bundle for #ident #ty_generics #where_clause {
+            type Inner<'a> = dyn #tr + 'a where Self: 'a;
+
+            #[inline]
+            fn inner(&mut self) -> &mut Self::Inner<'_> {
+                #ident::inner(self)
+            }
+        }
+    })
 }
@@ -11,6 +11,19 @@ mod dispatch_bundle;
 /// A bundle can hold a finite number of types that implement a common trait.
 /// The size of the bundle is known at compile time and equal to the size of the largest type in the bundle.
 /// Bundles are useful for type-erasure when transporting multiple types pseudo-heterogeneously.
+///
+/// # Arguments
+///
+/// - `Trait`: the path of the common trait, e.g. `#[bundle(path::to::Trait)]`.
+/// - `crate = path`: the path to `dispatch_bundle`, e.g. `#[bundle(Trait, crate = path::to::dispatch_bundle)]`
+///   when used through a re-export.
+///
+/// Besides `inner`, bundles implement `dispatch_bundle::Bundle`.
+///
+/// # Note
+///
+/// Requires `dispatch_bundle` to be in scope with that name,
+/// unless specified by `crate = ...`.
 #[proc_macro_attribute]
 pub fn bundle(attr: TokenStream, item: TokenStream) -> TokenStream {
     dispatch_bundle::bundle(attr, item)
@@ -18,22 +31,164 @@ pub fn bundle(attr: TokenStream, item: TokenStream) -> TokenStream {
 
 /// Generates the implementation block for conforming to `SerializeIter` of the "vanilla" flavor.
 ///
+/// `deserialize_in_place` is implemented recursively, so fields are overwritten without
+/// temporaries of the whole type. Enum variants are only overwritten in place if the
+/// deserialized variant is the one held, and fields with `scale`, `repr`, `with` or `len`
+/// are deserialized by value.
+///
+/// With the `detailed-errors` feature, `deserialize_iter_detailed` is implemented to
+/// report the offset, field path and reason of failures, which `deserialize_iter_endian`
+/// then discards.
+///
+/// # Container Attributes
+///
+/// - `#[cookie(crate = path::to::cookie_cutter)]`: the path to `cookie_cutter`, e.g.
+///   `embedded_command::cookie_cutter` when used through a re-export.
+/// - `#[cookie(endian = "big")]`: serialize multi-word values within the type, including
+///   enum tags and nested types, in the given order (`"little"` or `"big"`) regardless
+///   of the order of the enclosing type. Vanilla is little endian by default.
+/// - `#[cookie(validate = path::to::fn)]`: upon deserialization, reject values for which
+///   the given `fn(&Self) -> bool` is false with `Invalid`. Runs after field validation.
+/// - `#[cookie(magic = 0xA55A)]`: precede the type with a constant on the wire, which is
+///   rejected with `Invalid` on mismatch upon deserialization. The constant is an integer
+///   literal, typed by its suffix or the smallest unsigned type holding it, or a byte string.
+/// - `#[cookie(version = 3)]`: precede the type (after any magic constant) with its
+///   current version, a `u8` unless suffixed (`3u16`). Newer versions are rejected with
+///   `Invalid` upon deserialization. The serialized size accommodates every version.
+/// - `#[cookie(pad = 3)]`, `#[cookie(align = 4)]`: follow the type with the given number
+///   of reserved words, then with as many as needed for its size to be a multiple of
+///   `align`. Reserved words are zero-filled. Alignment is not supported by `SerializeBuf`
+///   for generic types.
+/// - `#[cookie(tag = hash)]`: derive enum tags from the FNV-1a (64 bit) hash of the variant
+///   names truncated to the tag type (`u32` unless specified), rather than from the discriminants, so tags are
+///   stable when variants are reordered. Collisions are rejected at compile time.
+/// - `#[cookie(tag_type = u8)]`: serialize enum tags as the given type. Otherwise, tags
+///   are of the `#[repr]` type of the enum, or the smallest unsigned type holding all tags
+///   if there is none, in which case explicit discriminants must be integer literals.
+/// - `#[cookie(reserved = "zero")]`: upon deserialization, reject reserved words within the
+///   type which are not zero with `Invalid`, instead of ignoring them (`"ignore"`).
+/// - `#[cookie(pod)]`: the type is serialized as its memory image, so `serialize_buf` and
+///   `deserialize_buf` copy it at once on little endian targets (see `Pod`). The struct must
+///   be `#[repr(C)]`, without padding, generics, other attributes or fields which are not
+///   `Pod` themselves, which is checked at compile time. Requires `SerializeBuf`.
+///
+/// # Field Attributes
+///
+/// - `#[cookie(scale = 0.01, repr = i16)]`: serialize a floating point field as
+///   the nearest integer multiple of `scale`, saturating at the bounds of `repr`.
+/// - `#[cookie(repr = half::f16)]`: serialize a field as a narrower type,
+///   widening it again on deserialization (see `Narrow`).
+/// - `#[cookie(with = path::to::module)]`: serialize the field with the functions
+///   `serialize_iter(&T, dst)`, `deserialize_iter(src) -> Result<T, _>` and
+///   `serialized_len(&T) -> usize` of the given module, which must also provide
+///   `const SIZE: with::Size`, the maximum serialized size. As `SerializeBuf` relies on
+///   it, the size is constructed unsafely (see `with::Size`).
+/// - `#[cookie(endian = "big")]`: serialize multi-word values within the field in the
+///   given order, regardless of the order of the enclosing type.
+/// - `#[cookie(len = count)]`: serialize only as many elements of an array or
+///   `heapless::Vec` as given by the earlier field `count`, or as many as are present if
+///   fewer. Negative counts select no elements upon serialization, and are rejected with
+///   `Invalid` upon deserialization, as are counts exceeding the capacity. The serialized
+///   size is that of a full collection.
+/// - `#[cookie(magic = 2)]`: serialize the given constant instead of the value of the
+///   field, rejecting mismatches with `Invalid` upon deserialization.
+/// - `#[cookie(pad = 3)]`, `#[cookie(align = 4)]`: precede the field with the given number
+///   of reserved words, then with as many as needed for its offset from the start of the
+///   type to be a multiple of `align`.
+/// - `#[cookie(range = 0..=100)]`: upon deserialization, reject values outside of the
+///   given range with `Invalid`.
+/// - `#[cookie(validate = path::to::fn)]`: upon deserialization, reject values for which
+///   the given `fn(&T) -> bool` is false with `Invalid`.
+/// - `#[cookie(skip)]`: do not serialize the field, it is excluded from the serialized size.
+///   Upon deserialization, the field is `Default::default()`, or the expression given by
+///   `#[cookie(default = ...)]`.
+/// - `#[cookie(since = 2)]`, `#[cookie(until = 3)]`: the field is only present in versions
+///   `since..until`. Upon deserialization of other versions, the field is
+///   `Default::default()`, or the expression given by `#[cookie(default = ...)]`.
+///
+/// # Variant Attributes
+///
+/// - `#[cookie(fallback)]`: deserialize unknown tags to this variant instead of failing.
+///   The variant must have exactly one field of the tag type which holds the tag,
+///   e.g. `Unknown(u8)` or `Unknown { tag: u8 }`. The tag is re-emitted on serialization.
+///
 /// # Note
 ///
-/// Requires `cookie_cutter` to be in scope with that name.
-#[proc_macro_derive(SerializeIter)]
+/// Requires `cookie_cutter` to be in scope with that name,
+/// unless specified by `#[cookie(crate = ...)]`.
+#[proc_macro_derive(SerializeIter, attributes(cookie))]
 pub fn serialize_iter_vanilla(item: TokenStream) -> TokenStream {
     cookie_cutter::vanilla::serialize_iter(item)
 }
 
 /// Generates the implementation block for conforming to `SerializeBuf` of the "vanilla" flavor.
 ///
-/// As of now, generic types *cannot* implement `SerializeBuf` on stable.
+/// Generic types are supported, but the `Serialized` type is then a composition
+/// of the mediums of the fields rather than an array, so any type parameters
+/// used in fields must be bounded by `SerializeBuf`.
+///
+/// Also generates the associated constants `OFFSET_<FIELD>` and `SIZE_<FIELD>`
+/// for each serialized field (`OFFSET_<VARIANT>_<FIELD>` for enums, of which
+/// payloads follow the tag), and `LAYOUT`, a table of `FieldLayout`s. Offsets are
+/// only constant up to the first field which is not always serialized to its maximum
+/// size, i.e. a `len` or versioned field or a type such as an enum with variants of
+/// different sizes. Later offsets are `None` in `LAYOUT`, and their `OFFSET_<FIELD>`
+/// constants fail to compile when used.
+///
+/// `SCHEMA` describes the serialized fields, enum tags and the attributes affecting
+/// their encoding. Fields serialized `with` a module are opaque, named after the path
+/// of the module as written. `FINGERPRINT`, a hash of `SCHEMA` ignoring names and
+/// the spelling of literal constants and range bounds, lets peers detect incompatible
+/// layouts.
 ///
 /// # Note
 ///
-/// Requires `cookie_cutter` to be in scope with that name.
-#[proc_macro_derive(SerializeBuf)]
+/// Accepts the same attributes as `SerializeIter`. Requires `cookie_cutter`
+/// to be in scope with that name, unless specified by `#[cookie(crate = ...)]`.
+#[proc_macro_derive(SerializeBuf, attributes(cookie))]
 pub fn serialize_buf_vanilla(item: TokenStream) -> TokenStream {
     cookie_cutter::vanilla::serialize_buf(item)
 }
+
+/// Generates `<Type>View<'a>` and `<Type>ViewMut<'a>`, views of the `SerializeBuf`
+/// medium of a struct of the "vanilla" flavor, which decode (and encode) individual
+/// fields at their constant offsets rather than deserializing the whole struct.
+///
+/// Views are validated upon construction, failing with `Invalid` if the medium does
+/// not deserialize, so getters, named after their fields (`get_0` for tuple structs),
+/// return the decoded value. Setters (`set_<field>`) fail with `Invalid` if the value
+/// would be rejected upon deserialization, keeping the medium valid. Accessors are as
+/// visible as their fields, and constant and skipped fields have none.
+///
+/// Offsets are constant when every field serializes to its full medium, so fields
+/// must not be length-prefixed or versioned, and accessed fields must not follow types
+/// which vary in size, such as enums with payloads of differing sizes. Both are
+/// rejected at compile time.
+///
+/// # Note
+///
+/// Requires `SerializeBuf` to be derived. Accepts the same attributes as `SerializeIter`.
+/// Requires `cookie_cutter` to be in scope with that name, unless specified by
+/// `#[cookie(crate = ...)]`.
+#[proc_macro_derive(SerializeView, attributes(cookie))]
+pub fn serialize_view_vanilla(item: TokenStream) -> TokenStream {
+    cookie_cutter::vanilla::serialize_view(item)
+}
+
+/// Generates the implementation block for conforming to `Decode` of the "vanilla" flavor,
+/// with which values are deserialized incrementally by a `Decoder` as words arrive.
+///
+/// The state of a partial value holds the fields deserialized so far and the progress
+/// of the current one, so it is at least as large as the type. The state of an enum
+/// holds the fields of the deserialized variant only, once its tag is known.
+///
+/// # Note
+///
+/// Requires `SerializeIter` to be derived, and the fields to implement `Decode`.
+/// Accepts the same attributes as `SerializeIter`, except `with`. Requires
+/// `cookie_cutter` to be in scope with that name, unless specified by
+/// `#[cookie(crate = ...)]`.
+#[proc_macro_derive(Decode, attributes(cookie))]
+pub fn decode_vanilla(item: TokenStream) -> TokenStream {
+    cookie_cutter::vanilla::decode(item)
+}